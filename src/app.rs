@@ -8,21 +8,88 @@
  * Note: `super::` & `self::` are relative to the current module while `crate::` is relative to the
  * crate root.
  */
-use crate::config::{runtime::Operation, runtime::RuntimeConfig};
+use crate::config::{
+    cli::STDOUT_OUTPUT_FILE, configs::RepoConfig, runtime::Annotation, runtime::Operation,
+    runtime::OutputFormat, runtime::OutputTarget, runtime::RuntimeConfig,
+};
+use crate::convert::{to_dockerignore, to_hgignore};
+use crate::detect::detect_templates;
+use crate::diff::{colorize_diff, unified_diff};
 use crate::errors::{Error, ErrorKind};
-use crate::git::{fetch_repository, update_gitignore_repos};
-
+use crate::git::{
+    configure_global_excludes_file, exclude_file_path, fetch_repository, global_excludes_file_path,
+    hash_blob, head_commit_id, read_blob, update_gitignore_repos,
+};
+use crate::lock::{lock_file_path, LockedFile, LockedOptions, Lockfile};
+use crate::lookup::{
+    expand_aliases, expand_bundles, match_recorded_template, match_repository,
+    match_template_names, match_template_path, split_repo_qualifier, suggest_template_names,
+    BUNDLE_PREFIX, REPO_QUALIFIER_DELIMITER,
+};
+use crate::managed::{find_managed_block, merge_managed_block, wrap_managed_block};
+use crate::preprocess::{project_variables, Preprocessor, Variables};
+use crate::provenance::{annotate_lines, annotate_section, SourceFile};
+use crate::rules::{
+    classify_line, dedup_sections, find_conflicts, tidy_section, trim_pattern, RuleLine,
+};
+use crate::template::{edit_template, new_template, save_template};
+use crate::utils::create_file;
+
+use std::cell::RefCell;
 use std::collections::btree_map::BTreeMap;
 use std::error::Error as StdErr;
-use std::fs::{self, DirEntry, File};
+use std::fs::{self, DirEntry};
 use std::io::{self, prelude::*};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use regex::Regex;
-
 /// `Binary tree hash-map` alias for simplicity.
-type TemplatePaths = BTreeMap<String, Vec<String>>;
+type TemplatePaths = BTreeMap<String, Vec<TemplateFile>>;
+
+/// `Vector` alias holding the files of the selected templates, in order of their sections.
+type SelectedTemplates = Vec<(String, Vec<TemplateFile>)>;
+
+/// `struct` containing the location of a gitignore template file within a cached repository.
+#[derive(Debug, Clone, PartialEq)]
+struct TemplateFile {
+    /// Cache path of the template's repository, as in [`RepoConfig::path`].
+    repo: String,
+
+    /// Path of the template file relative to the repository's root.
+    relative_path: String,
+
+    /// Absolute path of the template file.
+    path: String,
+
+    /// Commit to read the template file's content at, rather than the working tree.
+    commit: Option<String>,
+
+    /// Expected git blob hash of the template file's content, as recorded in a [`Lockfile`].
+    hash: Option<String>,
+}
+
+/// Method implementations for [`TemplateFile`].
+impl TemplateFile {
+    /// Returns the template's path, its relative path without the file type (i.e. `Global/macOS`).
+    fn template_path(&self) -> String {
+        Path::new(&self.relative_path)
+            .with_extension("")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Returns the template's path as an unambiguous template argument, root templates' paths
+    /// anchored by a leading `/` (i.e. `/Node` rather than `Node`).
+    fn anchored_template_path(&self) -> String {
+        let template_path = self.template_path();
+
+        if template_path.contains('/') {
+            template_path
+        } else {
+            format!("/{}", template_path)
+        }
+    }
+}
 
 /// Macro used to reduce repetition when defining a cached repository's absolute path.
 #[macro_export]
@@ -38,14 +105,12 @@ const TEMPLATE_LIST_OUTPUT_LIMIT: usize = 100;
 /// Const specifying the file content delimiter used.
 const FILE_CONTENT_DELIMITER: &str = "# ----";
 
+/// Const specifying the prefix of the generated gitignore's line listing the templates used.
+const TEMPLATES_USED_PREFIX: &str = "# Templates used:";
+
 /// Const specifying the delimiter for supplementary template content
 const TEMPLATE_SUPPLEMENT_DELIMITER: &str = "# ****";
 
-lazy_static! {
-    static ref GITIGNORE_ENTRY_REGEX: Regex =
-        Regex::new(r"[\*/!]").expect("failed to compile gitignore entry regex");
-}
-
 /// Handles the execution of `ignore`'s functions.
 ///
 /// Using the parsed [`RuntimeConfig`], this function runs a task specified by the user in `ignore`'s
@@ -76,9 +141,17 @@ pub fn run(mut app_confg: RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
 
     match app_confg.operation {
         Operation::GenerateGitignore => generate_gitignore(&mut app_confg)?,
+        Operation::CheckGitignore => check_gitignore(&mut app_confg)?,
+        Operation::DiffGitignore => diff_gitignore(&mut app_confg)?,
+        Operation::RegenerateGitignore => regenerate_gitignore(&mut app_confg)?,
+        Operation::AddTemplates => update_recorded_templates(&mut app_confg, true)?,
+        Operation::RemoveTemplates => update_recorded_templates(&mut app_confg, false)?,
         Operation::ListAvailableTemplates => list_templates(&mut app_confg)?,
         Operation::UpdateRepositories => update_gitignore_repos(&mut app_confg),
         Operation::GenerateCompletions => app_confg.generate_completions()?,
+        Operation::NewTemplate => new_template(&mut app_confg)?,
+        Operation::EditTemplate => edit_template(&mut app_confg)?,
+        Operation::SaveTemplate => save_template(&mut app_confg)?,
         Operation::Else => info!("app: no operation specified, this shouldn't have happened"),
     }
 
@@ -89,6 +162,14 @@ pub fn run(mut app_confg: RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
 ///
 /// This function calls [`parse_templates`] then [`concatenate_templates`]  for the user defined
 /// gitignore template arguments, yielding a consolidated gitignore file.
+/// The generated content replaces the output file's managed block, preserving user-written rules,
+/// unless [`RuntimeConfig::force_overwrite`] is set.
+/// For a [`STDOUT_OUTPUT_FILE`] output, only the generated content is written to stdout.
+/// Templates detected from [`RuntimeConfig::detect_dir`] supplement the user's templates.
+/// The output file is replaced by the git excludes file of a non-file [`OutputTarget`].
+/// The generated rules are converted to the ignore file format of [`RuntimeConfig::output_format`].
+/// Should [`RuntimeConfig::dry_run`] be set, the changes are printed by [`print_diff`] rather than
+/// written.
 ///
 /// # Examples
 ///
@@ -107,125 +188,944 @@ pub fn run(mut app_confg: RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
 ///
 /// ```
 fn generate_gitignore(app_confg: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
-    use std::fs::OpenOptions;
-
     info!("app: generating gitignore");
 
-    let available_templates = parse_templates(app_confg)?;
+    if let Some(detect_dir) = app_confg.detect_dir.clone() {
+        detect_project_templates(app_confg, &detect_dir)?;
+    }
+
+    let target_file = match app_confg.output_target {
+        OutputTarget::File => None,
+        OutputTarget::Exclude => Some(exclude_file_path(Path::new("."))?),
+        OutputTarget::Global => Some(global_excludes_file_path()?),
+    };
+    if let Some(target_file) = target_file {
+        debug!("app: targeting {}", target_file.display());
+        app_confg.gitignore_output_file = target_file.to_string_lossy().into_owned();
+    }
+
+    let lock_path = lock_file_path(&app_confg.gitignore_output_file);
+    let (template_paths, available_templates, template_arguments) = if app_confg.locked {
+        let lockfile = Lockfile::load(&lock_path)?;
+        apply_locked_options(app_confg, &lockfile.options);
+
+        let (template_paths, available_templates) = locked_template_paths(app_confg, &lockfile);
+        (template_paths, available_templates, lockfile.arguments)
+    } else {
+        let template_paths = generate_template_paths(app_confg)?;
+        let (available_templates, template_arguments) =
+            parse_templates(app_confg, &template_paths)?;
+
+        (template_paths, available_templates, template_arguments)
+    };
     debug!("app: available templates {:#?}", available_templates);
 
-    let mut consolidation_file = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(&app_confg.gitignore_output_file)?;
-    debug!("app: opened gitignore template consolidation file");
+    let (consolidation_string, mut lockfile) =
+        concatenate_templates(app_confg, &template_paths, available_templates)?;
+    lockfile.arguments = template_arguments;
+    let consolidation_string = convert_output(app_confg, consolidation_string);
+
+    if app_confg.gitignore_output_file == STDOUT_OUTPUT_FILE {
+        if app_confg.dry_run {
+            print_diff(STDOUT_OUTPUT_FILE, "", &consolidation_string);
+            return Ok(());
+        }
+
+        io::stdout().write_all(consolidation_string.as_bytes())?;
+        debug!("app: wrote generated gitignore to stdout");
+
+        return Ok(());
+    }
+
+    let existing_string = match fs::read_to_string(&app_confg.gitignore_output_file) {
+        Ok(existing_string) => existing_string,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(Box::new(err)),
+    };
+
+    let output_string = if app_confg.force_overwrite {
+        wrap_managed_block(&consolidation_string)
+    } else {
+        debug!("app: merging generated content into the managed block");
+        merge_managed_block(
+            &enclose_generated_content(&existing_string, &app_confg.config.output.header),
+            &consolidation_string,
+        )?
+    };
 
-    consolidation_file.set_len(0)?;
+    if app_confg.dry_run {
+        info!("app: dry run, skipping gitignore generation");
+        print_diff(
+            &app_confg.gitignore_output_file,
+            &existing_string,
+            &output_string,
+        );
 
-    let consolidation_string = concatenate_templates(&app_confg.templates, available_templates)?;
-    consolidation_file.write_all(consolidation_string.as_bytes())?;
+        return Ok(());
+    }
+
+    let output_path = Path::new(&app_confg.gitignore_output_file);
+    if !output_path.exists() {
+        create_file(output_path)?;
+    }
+    fs::write(output_path, output_string)?;
     info!(
         "app: generated gitignore {}",
         app_confg.gitignore_output_file
     );
 
+    // The git excludes files lack a lockfile, `.lock` files within `.git` being git's own.
+    match app_confg.output_target {
+        OutputTarget::File => {
+            lockfile.save(&lock_path)?;
+            info!("app: locked gitignore templates in {}", lock_path.display());
+        }
+        OutputTarget::Exclude => {}
+        OutputTarget::Global => configure_global_excludes_file(output_path)?,
+    }
+
+    Ok(())
+}
+
+/// Checks a generated gitignore file for drift from its templates.
+///
+/// This function regenerates the gitignore file's content in memory, from the templates recorded
+/// in its lockfile or its [`TEMPLATES_USED_PREFIX`] line, comparing it to the file's content.
+/// Should a lockfile exist, the templates' files are also compared to the locked ones.
+/// Drift is reported to stdout, failing with an [`ErrorKind::Drift`] error.
+fn check_gitignore(app_confg: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    info!(
+        "app: checking gitignore {}",
+        app_confg.gitignore_output_file
+    );
+
+    let existing_string = fs::read_to_string(&app_confg.gitignore_output_file)?;
+    let lockfile = load_recorded_templates(app_confg, &existing_string)?;
+
+    let template_paths = generate_template_paths(app_confg)?;
+    let (available_templates, _) = parse_templates(app_confg, &template_paths)?;
+    let (consolidation_string, current_lockfile) =
+        concatenate_templates(app_confg, &template_paths, available_templates)?;
+    let consolidation_string = convert_output(app_confg, consolidation_string);
+
+    let mut drifts = Vec::<String>::new();
+    if let Some(lockfile) = lockfile {
+        let same_file = |locked_file: &LockedFile, current_file: &LockedFile| {
+            locked_file.template == current_file.template
+                && locked_file.repo == current_file.repo
+                && locked_file.path == current_file.path
+                && locked_file.hash == current_file.hash
+        };
+
+        let mut changed_templates = Vec::<&str>::new();
+        for (files, other_files) in [
+            (&lockfile.files, &current_lockfile.files),
+            (&current_lockfile.files, &lockfile.files),
+        ] {
+            for file in files.iter() {
+                let unchanged = other_files
+                    .iter()
+                    .any(|other_file| same_file(file, other_file));
+                if !unchanged && !changed_templates.contains(&file.template.as_str()) {
+                    changed_templates.push(&file.template);
+                }
+            }
+        }
+
+        for template in changed_templates {
+            drifts.push(format!("template {} changed since it was locked", template));
+        }
+    }
+
+    let merged_string = merge_managed_block(
+        &enclose_generated_content(&existing_string, &app_confg.config.output.header),
+        &consolidation_string,
+    )?;
+    if merged_string != existing_string {
+        drifts.push(format!(
+            "{} differs from its regenerated content",
+            app_confg.gitignore_output_file
+        ));
+    }
+
+    if drifts.is_empty() {
+        info!(
+            "app: gitignore {} is up to date",
+            app_confg.gitignore_output_file
+        );
+
+        return Ok(());
+    }
+
+    for drift in drifts.iter() {
+        println!("{}", drift);
+    }
+
+    Err(Box::new(Error::with_message(
+        ErrorKind::Drift,
+        app_confg.gitignore_output_file.to_owned(),
+    )))
+}
+
+/// Regenerates a generated gitignore file from its recorded templates.
+///
+/// This function loads the templates recorded by [`load_recorded_templates`], regenerating the
+/// gitignore file against the current template repositories by [`generate_gitignore`] (preserving
+/// the content surrounding its managed block).
+fn regenerate_gitignore(app_confg: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    info!(
+        "app: regenerating gitignore {}",
+        app_confg.gitignore_output_file
+    );
+
+    let existing_string = fs::read_to_string(&app_confg.gitignore_output_file)?;
+    load_recorded_templates(app_confg, &existing_string)?;
+    debug!("app: recorded templates {:?}", app_confg.templates);
+
+    generate_gitignore(app_confg)
+}
+
+/// Adds templates to (or removes templates from) a generated gitignore file.
+///
+/// This function updates the templates recorded by [`load_recorded_templates`] with the user's
+/// template arguments, regenerating the gitignore file by [`generate_gitignore`] (preserving the
+/// content surrounding its managed block).
+/// Removed template arguments (with bundles & aliases expanded) are matched by
+/// [`match_recorded_template`], failing should an argument match no recorded template.
+fn update_recorded_templates(
+    app_confg: &mut RuntimeConfig,
+    add: bool,
+) -> Result<(), Box<dyn StdErr>> {
+    info!(
+        "app: updating templates of gitignore {}",
+        app_confg.gitignore_output_file
+    );
+
+    let template_args = std::mem::take(&mut app_confg.templates);
+    let existing_string = fs::read_to_string(&app_confg.gitignore_output_file)?;
+    load_recorded_templates(app_confg, &existing_string)?;
+
+    if add {
+        app_confg.templates.extend(template_args);
+    } else {
+        let removed_templates = expand_aliases(
+            &expand_bundles(&template_args, &app_confg.config.bundles)?,
+            &app_confg.config.aliases,
+        );
+
+        for removed_template in removed_templates.iter() {
+            let recorded_count = app_confg.templates.len();
+            app_confg
+                .templates
+                .retain(|template| !match_recorded_template(removed_template, template));
+
+            if app_confg.templates.len() == recorded_count {
+                return Err(Box::new(Error::with_message(
+                    ErrorKind::MissingTemplates,
+                    format!(
+                        "`{}` isn't used by {}",
+                        removed_template, app_confg.gitignore_output_file
+                    ),
+                )));
+            }
+        }
+
+        if app_confg.templates.is_empty() {
+            return Err(Box::new(Error::from(format!(
+                "removing {} would leave {} without templates",
+                template_args.join(" "),
+                app_confg.gitignore_output_file
+            ))));
+        }
+    }
+    debug!("app: updated templates {:?}", app_confg.templates);
+
+    generate_gitignore(app_confg)
+}
+
+/// Previews the regeneration of a generated gitignore file as a unified diff.
+///
+/// This function regenerates the gitignore file's content in memory, from the templates recorded
+/// in its lockfile or its [`TEMPLATES_USED_PREFIX`] line, printing the changes by [`print_diff`].
+fn diff_gitignore(app_confg: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    info!("app: diffing gitignore {}", app_confg.gitignore_output_file);
+
+    let existing_string = fs::read_to_string(&app_confg.gitignore_output_file)?;
+    load_recorded_templates(app_confg, &existing_string)?;
+
+    let template_paths = generate_template_paths(app_confg)?;
+    let (available_templates, _) = parse_templates(app_confg, &template_paths)?;
+    let (consolidation_string, _) =
+        concatenate_templates(app_confg, &template_paths, available_templates)?;
+    let consolidation_string = convert_output(app_confg, consolidation_string);
+
+    print_diff(
+        &app_confg.gitignore_output_file,
+        &existing_string,
+        &merge_managed_block(
+            &enclose_generated_content(&existing_string, &app_confg.config.output.header),
+            &consolidation_string,
+        )?,
+    );
+
+    Ok(())
+}
+
+/// Converts generated gitignore content to the [`RuntimeConfig::output_format`].
+fn convert_output(app_confg: &RuntimeConfig, consolidation_string: String) -> String {
+    match app_confg.output_format {
+        OutputFormat::Gitignore => consolidation_string,
+        OutputFormat::Dockerignore => to_dockerignore(&consolidation_string),
+        OutputFormat::Hgignore => to_hgignore(&consolidation_string),
+    }
+}
+
+/// Prints the changes between the current & generated content of a gitignore file to stdout.
+///
+/// The unified diff's hunks are headed by their template section, the diff being colorized for
+/// terminals.
+fn print_diff(file: &str, existing_string: &str, output_string: &str) {
+    use std::io::IsTerminal;
+
+    let is_section_heading = |lines: &[&str], index: usize| {
+        index > 0
+            && lines[index - 1].is_empty()
+            && lines[index].starts_with("# ")
+            && lines.get(index + 1) == Some(&FILE_CONTENT_DELIMITER)
+    };
+
+    let diff = unified_diff(
+        existing_string,
+        output_string,
+        file,
+        &format!("{} (generated)", file),
+        &is_section_heading,
+    );
+    if diff.is_empty() {
+        info!("app: gitignore {} is up to date", file);
+        return;
+    }
+
+    if io::stdout().is_terminal() {
+        print!("{}", colorize_diff(&diff));
+    } else {
+        print!("{}", diff);
+    }
+}
+
+/// Loads the templates recorded for a generated gitignore file into [`RuntimeConfig::templates`].
+///
+/// The templates are read from the gitignore file's lockfile (its template arguments, should it
+/// record them), which is returned, or its [`TEMPLATES_USED_PREFIX`] line.
+/// The lockfile's options are applied by [`apply_locked_options`], such that the gitignore file is
+/// regenerated as it was generated.
+fn load_recorded_templates(
+    app_confg: &mut RuntimeConfig,
+    existing_string: &str,
+) -> Result<Option<Lockfile>, Box<dyn StdErr>> {
+    let lock_path = lock_file_path(&app_confg.gitignore_output_file);
+    let lockfile = if lock_path.is_file() {
+        Some(Lockfile::load(&lock_path)?)
+    } else {
+        None
+    };
+
+    app_confg.templates = match &lockfile {
+        Some(lockfile) => {
+            apply_locked_options(app_confg, &lockfile.options);
+
+            if lockfile.arguments.is_empty() {
+                lockfile.templates.clone()
+            } else {
+                lockfile.arguments.clone()
+            }
+        }
+        None => templates_used(existing_string).ok_or_else(|| {
+            Error::from(format!(
+                "{} lacks a lockfile & a `{}` line",
+                app_confg.gitignore_output_file, TEMPLATES_USED_PREFIX
+            ))
+        })?,
+    };
+
+    Ok(lockfile)
+}
+
+/// Collects the options affecting the generated gitignore's content, as recorded in a
+/// [`Lockfile`].
+fn locked_options(app_confg: &RuntimeConfig) -> LockedOptions {
+    LockedOptions {
+        format: app_confg.output_format.clone(),
+        alpha_sort: app_confg.alpha_sort,
+        annotate: app_confg.annotation.clone(),
+        global_dedup: app_confg.global_dedup,
+        strip_comments: app_confg.strip_comments,
+        compact: app_confg.compact,
+        normalize: app_confg.normalize,
+        variables: app_confg.template_variables.clone(),
+    }
+}
+
+/// Applies the options recorded in a [`Lockfile`] (see [`locked_options`]) to the
+/// [`RuntimeConfig`].
+fn apply_locked_options(app_confg: &mut RuntimeConfig, options: &LockedOptions) {
+    debug!("app: applying locked options {:?}", options);
+
+    app_confg.output_format = options.format.clone();
+    app_confg.alpha_sort = options.alpha_sort;
+    app_confg.annotation = options.annotate.clone();
+    app_confg.global_dedup = options.global_dedup;
+    app_confg.strip_comments = options.strip_comments;
+    app_confg.compact = options.compact;
+    app_confg.normalize = options.normalize;
+    app_confg.template_variables = options.variables.clone();
+}
+
+/// Obtains the templates listed by a generated gitignore's [`TEMPLATES_USED_PREFIX`] line.
+///
+/// The line is searched for within the managed block, should the gitignore have one.
+fn templates_used(gitignore: &str) -> Option<Vec<String>> {
+    let generated = match find_managed_block(gitignore) {
+        Ok(Some((start, end))) => &gitignore[start..end],
+        _ => gitignore,
+    };
+
+    generated.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix(TEMPLATES_USED_PREFIX)
+            .map(|templates| templates.split_whitespace().map(str::to_owned).collect())
+    })
+}
+
+/// Encloses the generated content of a gitignore lacking a managed block within the managed block
+/// markers, such that [`merge_managed_block`] replaces it rather than appending to it.
+///
+/// The generated content of a gitignore written to stdout (or before the managed block) spans its
+/// header, as in the lines of the `header` preceding its [`TEMPLATES_USED_PREFIX`] line, to its
+/// last [`FILE_CONTENT_DELIMITER`] line.
+/// Gitignore content with a managed block or lacking a [`TEMPLATES_USED_PREFIX`] line is returned
+/// unchanged.
+fn enclose_generated_content(gitignore: &str, header: &str) -> String {
+    if !matches!(find_managed_block(gitignore), Ok(None)) {
+        return gitignore.to_owned();
+    }
+
+    let lines: Vec<&str> = gitignore.split_inclusive('\n').collect();
+    let is_templates_used = |line: &str| line.trim().starts_with(TEMPLATES_USED_PREFIX);
+    let templates_used_index = match lines.iter().position(|line| is_templates_used(line)) {
+        Some(templates_used_index) => templates_used_index,
+        None => return gitignore.to_owned(),
+    };
+
+    let header_length = header
+        .lines()
+        .take_while(|line| !is_templates_used(line))
+        .count();
+    let start_index = (0..templates_used_index)
+        .rev()
+        .take(header_length)
+        .take_while(|index| {
+            let line = lines[*index].trim();
+            line.is_empty() || line.starts_with('#')
+        })
+        .last()
+        .unwrap_or(templates_used_index);
+    let end_index = lines
+        .iter()
+        .rposition(|line| line.trim_end() == FILE_CONTENT_DELIMITER)
+        .filter(|end_index| *end_index > templates_used_index)
+        .unwrap_or(templates_used_index);
+
+    let offset = |index: usize| lines[..index].iter().map(|line| line.len()).sum::<usize>();
+    let (start, end) = (offset(start_index), offset(end_index + 1));
+
+    format!(
+        "{}{}{}",
+        &gitignore[..start],
+        wrap_managed_block(&gitignore[start..end]),
+        &gitignore[end..]
+    )
+}
+
+/// Generates [`TemplatePaths`] for the template files recorded in a [`Lockfile`].
+///
+/// This function yields the [`TemplatePaths`] of all locked files (included templates' too) &
+/// the [`SelectedTemplates`] of the locked templates (in locked order), the files being read at
+/// their locked commit.
+fn locked_template_paths(
+    app_confg: &RuntimeConfig,
+    lockfile: &Lockfile,
+) -> (TemplatePaths, SelectedTemplates) {
+    let mut template_paths = TemplatePaths::new();
+
+    for locked_file in lockfile.files.iter() {
+        let repo_dir = Path::new(&app_confg.config.repository.cache_dir).join(&locked_file.repo);
+
+        template_paths
+            .entry(locked_file.template.to_owned())
+            .or_default()
+            .push(TemplateFile {
+                repo: locked_file.repo.to_owned(),
+                relative_path: locked_file.path.to_owned(),
+                path: repo_dir
+                    .join(&locked_file.path)
+                    .to_string_lossy()
+                    .into_owned(),
+                commit: locked_file.commit.clone(),
+                hash: Some(locked_file.hash.to_owned()),
+            });
+    }
+
+    let available_templates = lockfile
+        .templates
+        .iter()
+        .filter_map(|template| {
+            template_paths
+                .get(template)
+                .map(|template_files| (template.to_owned(), template_files.to_owned()))
+        })
+        .collect();
+
+    (template_paths, available_templates)
+}
+
+/// Appends the templates detected within a project's working tree to the user's templates.
+///
+/// This function calls [`detect_templates`] for the project's working tree, reporting the detected
+/// templates & the reasons for their detection on a dry run.
+fn detect_project_templates(
+    app_confg: &mut RuntimeConfig,
+    detect_dir: &Path,
+) -> Result<(), Box<dyn StdErr>> {
+    info!("app: detecting templates in {}", detect_dir.display());
+
+    let detections = detect_templates(detect_dir)?;
+    if detections.is_empty() {
+        warn!("app: no templates detected in {}", detect_dir.display());
+    }
+
+    for (template, reasons) in detections {
+        if app_confg.dry_run {
+            println!("{}", template);
+            for reason in reasons.iter() {
+                println!("    {}", reason);
+            }
+        }
+
+        if !app_confg.templates.contains(&template) {
+            app_confg.templates.push(template);
+        }
+    }
+
     Ok(())
 }
 
 /// Concatenates gitignore template(s) specified by the user.
 ///
-/// This function acts on a [`TemplatePaths`] item for the template arguments specified by a user,
-/// consolidating the file paths listed within the item.
+/// This function acts on a [`SelectedTemplates`] item for the template arguments specified by a
+/// user, consolidating the file paths listed within the item in order.
+/// The content of each template is preprocessed by a [`Preprocessor`], resolving included
+/// templates from the passed (complete) [`TemplatePaths`] item.
+/// Should [`RuntimeConfig::global_dedup`] be set, the patterns repeated across templates are
+/// deduplicated by [`dedup_sections`].
+/// Should [`RuntimeConfig::check_conflicts`] be set, the conflicts across templates are reported
+/// by [`check_conflicts`].
+/// Should [`RuntimeConfig::strip_comments`], [`RuntimeConfig::compact`] or
+/// [`RuntimeConfig::normalize`] be set, the sections are tidied by [`tidy_section`], the compact
+/// output also lacking blank lines between sections.
+/// Should [`RuntimeConfig::annotation`] be set, the sections are annotated with the origin of
+/// their content by [`annotate_section`] or [`annotate_lines`].
+/// The template files read are recorded in the returned [`Lockfile`].
 fn concatenate_templates(
-    requested_templates: &[String],
-    available_templates: TemplatePaths,
-) -> Result<String, Box<dyn StdErr>> {
-    let mut consolidation_string = String::new();
+    app_confg: &RuntimeConfig,
+    template_paths: &TemplatePaths,
+    available_templates: SelectedTemplates,
+) -> Result<(String, Lockfile), Box<dyn StdErr>> {
     let mut return_string = String::new();
-    let mut templates_used = String::new();
+    let mut sections = Vec::<(String, String)>::new();
 
     if available_templates.is_empty() {
         warn!(
-            "app: could not locate template(s) (names are case sensitive) {:?}",
-            requested_templates
+            "app: could not locate template(s) {:?}",
+            app_confg.templates
         );
         return Err(Box::new(Error::from(ErrorKind::MissingTemplates)));
     }
 
-    // Iterate over template_paths, opening necessary file & concatenating them.
-    for (template, file_paths) in available_templates {
-        let file_paths = &file_paths;
+    let lockfile = RefCell::new(Lockfile::default());
+    let sources = RefCell::new(Vec::<SourceFile>::new());
+    let include_loader = |template: &str| -> Result<String, Box<dyn StdErr>> {
+        let template_files = if app_confg.locked {
+            template_paths.get(template).cloned().ok_or_else(|| {
+                Error::with_message(
+                    ErrorKind::LockMismatch,
+                    format!("`{}` isn't locked", template),
+                )
+            })?
+        } else {
+            resolve_template(
+                template,
+                template_paths,
+                &app_confg.config.repository.config,
+            )
+            .map_err(|reason| {
+                Error::with_message(
+                    ErrorKind::MissingTemplates,
+                    format!("`{}`{}", template, reason),
+                )
+            })?
+            .1
+        };
 
-        let mut template_string = format!("\n# {}\n{}\n", template, FILE_CONTENT_DELIMITER);
+        Ok(
+            read_template(app_confg, template, &template_files, &lockfile, &sources)?
+                .unwrap_or_default(),
+        )
+    };
+    let variables = template_variables(app_confg);
+    let preprocessor = Preprocessor::new(&variables, &include_loader);
+
+    let mut source_repos = Vec::<(String, Option<String>)>::new();
+    for template_file in available_templates
+        .iter()
+        .flat_map(|(_, template_files)| template_files)
+    {
+        if !source_repos
+            .iter()
+            .any(|(source_repo, _)| *source_repo == template_file.repo)
+        {
+            source_repos.push((template_file.repo.to_owned(), template_file.commit.clone()));
+        }
+    }
+    source_repos.sort();
+
+    let mut section_sources = Vec::<Vec<SourceFile>>::new();
+    for (template, template_files) in available_templates {
+        let template_string =
+            match read_template(app_confg, &template, &template_files, &lockfile, &sources)? {
+                Some(template_string) => preprocessor.process(&template, &template_string)?,
+                None => continue,
+            };
 
-        let mut template_vec = Vec::<String>::new();
+        sections.push((template, template_string));
+        section_sources.push(sources.take());
+    }
 
-        for file_path in file_paths {
-            debug!("parsing: {}", file_path);
-            match File::open(file_path) {
-                Ok(mut template_file) => {
-                    let mut buffer = String::new();
+    if sections.is_empty() {
+        warn!("app: could not use template(s) {:?}", app_confg.templates);
+        return Err(Box::new(Error::from(ErrorKind::MissingTemplates)));
+    }
 
-                    template_file.read_to_string(&mut buffer)?;
-                    template_vec.push(buffer.to_owned());
+    if app_confg.check_conflicts {
+        check_conflicts(app_confg, &sections, &section_sources)?;
+    }
 
-                    debug!(
-                        "app: appended {} content to {} template vector",
-                        file_path, template
-                    );
-                }
-                Err(err) => {
-                    error!("app: failed to open gitignore template file {}", err);
-                    continue;
-                }
-            };
-        }
+    if app_confg.global_dedup {
+        info!("app: deduplicating gitignore template entries across templates");
+        dedup_sections(&mut sections);
+    }
 
-        if template_vec.is_empty() {
-            continue;
+    if app_confg.strip_comments || app_confg.compact || app_confg.normalize {
+        for (_, template_string) in sections.iter_mut() {
+            *template_string = tidy_section(
+                template_string,
+                app_confg.strip_comments,
+                app_confg.compact,
+                app_confg.normalize,
+            );
         }
+    }
 
-        template_vec.sort();
-        template_vec.dedup();
+    let templates_used: Vec<&str> = sections
+        .iter()
+        .map(|(template, _)| template.as_str())
+        .collect();
 
-        if template_vec.len().gt(&1) {
-            let deduped_string = dedup_templates(&template, template_vec.as_mut())?;
+    let mut output_vars = variables.clone();
+    output_vars.extend(output_variables(app_confg, &templates_used, &source_repos));
+    let output_preprocessor = Preprocessor::new(&output_vars, &include_loader);
 
-            templates_used.push_str(&format!(" {}", template));
-            template_string.push_str(&deduped_string);
-        } else {
-            templates_used.push_str(&format!(" {}", template));
-            template_string.push_str(&template_vec[0]);
-        }
-        template_string.push_str(&format!("{}\n", FILE_CONTENT_DELIMITER));
+    return_string
+        .push_str(&output_preprocessor.process("header", &app_confg.config.output.header)?);
+
+    let section_separator = if app_confg.compact { "" } else { "\n" };
+
+    for ((template, template_string), sources) in sections.iter().zip(section_sources.iter()) {
+        let template_string = match app_confg.annotation {
+            Some(Annotation::Section) => annotate_section(template_string, sources),
+            Some(Annotation::Line) => annotate_lines(template_string, sources),
+            None => template_string.to_owned(),
+        };
 
-        consolidation_string.push_str(&template_string);
+        return_string.push_str(&format!(
+            "{}# {}\n{}\n{}{}\n",
+            section_separator,
+            template,
+            FILE_CONTENT_DELIMITER,
+            template_string,
+            FILE_CONTENT_DELIMITER
+        ));
     }
 
-    if templates_used.is_empty() {
-        warn!(
-            "app: could not use template(s) (names are case sensitive) {:?}",
-            requested_templates
-        );
-        return Err(Box::new(Error::from(ErrorKind::MissingTemplates)));
+    if !app_confg.config.output.footer.is_empty() {
+        return_string.push_str(section_separator);
+        return_string
+            .push_str(&output_preprocessor.process("footer", &app_confg.config.output.footer)?);
+    }
+
+    let mut lockfile = lockfile.take();
+    lockfile.templates = templates_used
+        .iter()
+        .map(|template| template.to_string())
+        .collect();
+    lockfile.options = locked_options(app_confg);
+
+    Ok((return_string, lockfile))
+}
+
+/// Collects the variables available to the generated gitignore's header & footer.
+///
+/// The `sources` variable lists a comment line per template repository used, holding the
+/// repository's URL & commit ID: the commit its files were locked at, otherwise its `HEAD` (should
+/// it be a git repository).
+fn output_variables(
+    app_confg: &RuntimeConfig,
+    templates_used: &[&str],
+    source_repos: &[(String, Option<String>)],
+) -> Variables {
+    use std::env;
+
+    let sources: Vec<String> = source_repos
+        .iter()
+        .map(|(repo, locked_commit)| {
+            let mut source = format!("# {}", repo);
+            if let Some(conf) = app_confg
+                .config
+                .repository
+                .config
+                .iter()
+                .find(|conf| &conf.path == repo)
+            {
+                if !conf.url.is_empty() {
+                    source.push_str(&format!(" {}", conf.url));
+                }
+            }
+            let repo_dir = Path::new(&app_confg.config.repository.cache_dir).join(repo);
+            if let Some(commit_id) = locked_commit.clone().or_else(|| head_commit_id(&repo_dir)) {
+                source.push_str(&format!(" {}", commit_id));
+            }
+
+            source
+        })
+        .collect();
+
+    let mut variables = Variables::new();
+    variables.insert("version".to_owned(), crate_version!().to_owned());
+    variables.insert(
+        "time".to_owned(),
+        chrono::Local::now()
+            .format("%Y-%m-%dT%H:%M:%S%z")
+            .to_string(),
+    );
+    variables.insert(
+        "command".to_owned(),
+        env::args().collect::<Vec<String>>().join(" "),
+    );
+    variables.insert("templates".to_owned(), templates_used.join(" "));
+    variables.insert("sources".to_owned(), sources.join("\n"));
+
+    variables
+}
+
+/// Reads the content of a template's files.
+///
+/// This function deduplicates the content of multiple template files by [`dedup_templates`],
+/// returning `None` should none of the files be readable.
+/// The files read are recorded in the [`Lockfile`] & as [`SourceFile`]s (in order of
+/// consolidation), unreadable locked files failing the read.
+fn read_template(
+    app_confg: &RuntimeConfig,
+    template: &str,
+    template_files: &[TemplateFile],
+    lockfile: &RefCell<Lockfile>,
+    sources: &RefCell<Vec<SourceFile>>,
+) -> Result<Option<String>, Box<dyn StdErr>> {
+    let mut template_sources = Vec::<SourceFile>::new();
+
+    for template_file in template_files.iter() {
+        let file_path = &template_file.path;
+
+        debug!("parsing: {}", file_path);
+        match read_template_file(app_confg, template, template_file) {
+            Ok((content, locked_file)) => {
+                template_sources.push(SourceFile {
+                    origin: format!(
+                        "{}{}{}",
+                        locked_file.repo, REPO_QUALIFIER_DELIMITER, locked_file.path
+                    ),
+                    content,
+                });
+                lockfile.borrow_mut().push_file(locked_file);
+
+                debug!(
+                    "app: appended {} content to {} template vector",
+                    file_path, template
+                );
+            }
+            Err(err) if template_file.hash.is_some() => return Err(err),
+            Err(err) => {
+                error!(
+                    "app: failed to read gitignore template file {}: {}",
+                    file_path, err
+                );
+                continue;
+            }
+        };
+    }
+
+    if template_sources.is_empty() {
+        return Ok(None);
+    }
+
+    // The sources are recorded in the order their content is consolidated by `dedup_templates`.
+    template_sources.sort_by(|source, other_source| source.content.cmp(&other_source.content));
+    template_sources.dedup_by(|source, other_source| source.content == other_source.content);
+
+    let mut template_vec: Vec<String> = template_sources
+        .iter()
+        .map(|source| source.content.to_owned())
+        .collect();
+    sources.borrow_mut().extend(template_sources);
+
+    let mut template_string = if template_vec.len().gt(&1) {
+        dedup_templates(template, template_vec.as_mut())?
+    } else {
+        template_vec.swap_remove(0)
+    };
+    if !template_string.ends_with('\n') {
+        template_string.push('\n');
+    }
+
+    Ok(Some(template_string))
+}
+
+/// Reads the content of a template file, alongside its [`LockedFile`] record.
+///
+/// The content is read at [`TemplateFile::commit`] should it be set, otherwise from the working
+/// tree, failing should its hash differ from [`TemplateFile::hash`].
+/// The recorded commit is the repository's `HEAD`, should the content be committed as is.
+fn read_template_file(
+    app_confg: &RuntimeConfig,
+    template: &str,
+    template_file: &TemplateFile,
+) -> Result<(String, LockedFile), Box<dyn StdErr>> {
+    let repo_dir = Path::new(&app_confg.config.repository.cache_dir).join(&template_file.repo);
+    let relative_path = Path::new(&template_file.relative_path);
+
+    let content = match &template_file.commit {
+        Some(commit) => String::from_utf8(read_blob(&repo_dir, commit, relative_path)?)?,
+        None => fs::read_to_string(&template_file.path)?,
+    };
+
+    let hash = hash_blob(content.as_bytes())?;
+    if template_file
+        .hash
+        .as_ref()
+        .is_some_and(|locked_hash| *locked_hash != hash)
+    {
+        return Err(Box::new(Error::with_message(
+            ErrorKind::LockMismatch,
+            format!(
+                "{}{}{} changed",
+                template_file.repo, REPO_QUALIFIER_DELIMITER, template_file.relative_path
+            ),
+        )));
+    }
+
+    let commit = template_file.commit.clone().or_else(|| {
+        head_commit_id(&repo_dir).filter(|commit| {
+            read_blob(&repo_dir, commit, relative_path).is_ok_and(|blob| blob == content.as_bytes())
+        })
+    });
+    let url = app_confg
+        .config
+        .repository
+        .config
+        .iter()
+        .find(|conf| conf.path == template_file.repo)
+        .map(|conf| conf.url.to_owned())
+        .unwrap_or_default();
+
+    Ok((
+        content,
+        LockedFile {
+            template: template.to_owned(),
+            repo: template_file.repo.to_owned(),
+            url,
+            path: template_file.relative_path.to_owned(),
+            commit,
+            hash,
+        },
+    ))
+}
+
+/// Collects the template preprocessor variables.
+///
+/// This function merges the [`project_variables`] of the project (the detection directory, the
+/// output file's directory or the working directory for git excludes files) with the user's
+/// [`RuntimeConfig::template_variables`], the latter taking precedence.
+fn template_variables(app_confg: &RuntimeConfig) -> Variables {
+    let project_dir = match &app_confg.detect_dir {
+        Some(detect_dir) => detect_dir.to_owned(),
+        None if app_confg.output_target != OutputTarget::File => PathBuf::from("."),
+        None => Path::new(&app_confg.gitignore_output_file)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+    };
+
+    let mut variables = project_variables(&project_dir);
+    variables.extend(app_confg.template_variables.clone());
+
+    variables
+}
+
+/// Reports the conflicting rules across the consolidated template sections.
+///
+/// This function warns of each conflict found by [`find_conflicts`], failing should
+/// [`RuntimeConfig::deny_conflicts`] be set.
+fn check_conflicts(
+    app_confg: &RuntimeConfig,
+    sections: &[(String, String)],
+    section_sources: &[Vec<SourceFile>],
+) -> Result<(), Box<dyn StdErr>> {
+    info!("app: checking gitignore template entries for conflicts");
+
+    let conflicts = find_conflicts(sections, section_sources);
+    for conflict in conflicts.iter() {
+        warn!("app: conflict: {}", conflict);
     }
 
-    return_string.push_str("#\n# .gitignore\n#\n\n");
-    return_string.push_str(&format!(
-        "# Templates used:{}\n{}",
-        templates_used, consolidation_string
-    ));
+    if conflicts.is_empty() {
+        info!("app: no conflicts found");
+    } else if app_confg.deny_conflicts {
+        return Err(Box::new(Error::with_message(
+            ErrorKind::RuleConflicts,
+            format!("{} conflict(s) found", conflicts.len()),
+        )));
+    }
 
-    Ok(return_string)
+    Ok(())
 }
 
 /// Deduplicates gitignore template content.
+///
+/// This function retains the first template file's content, appending the rules of subsequent
+/// template files absent from it (compared as normalized rules, see [`classify_line`]) as
+/// supplementary content.
 fn dedup_templates(template: &str, template_vec: &mut [String]) -> Result<String, Box<dyn StdErr>> {
-    // FIXME: Review this function for a better approach if any.
-    // Iterating over all the lines for subsequent template files of a given technology seems
-    // wasteful, they shouldn't be more than one so...
+    use std::collections::HashSet;
 
     info!(
         "app: deduplicating gitignore template entries for {}",
@@ -235,17 +1135,17 @@ fn dedup_templates(template: &str, template_vec: &mut [String]) -> Result<String
     let primary_content = template_vec[0].clone();
     let mut insert_string = String::new();
 
+    let mut seen_rules: HashSet<RuleLine> = primary_content
+        .lines()
+        .map(classify_line)
+        .filter(|rule| rule.pattern().is_some())
+        .collect();
+
     for template_file in template_vec.iter().skip(1) {
         for line in template_file.lines() {
-            let trimmed_line = line.trim();
-
-            let invalid_line = {
-                !GITIGNORE_ENTRY_REGEX.is_match(trimmed_line)
-                    || primary_content.contains(trimmed_line)
-                    || insert_string.contains(trimmed_line)
-            };
+            let rule = classify_line(line);
 
-            if invalid_line {
+            if rule.pattern().is_none() || !seen_rules.insert(rule) {
                 continue;
             }
 
@@ -256,7 +1156,7 @@ fn dedup_templates(template: &str, template_vec: &mut [String]) -> Result<String
                     template, TEMPLATE_SUPPLEMENT_DELIMITER
                 ));
             }
-            insert_string.push_str(&format!("{}\n", trimmed_line));
+            insert_string.push_str(&format!("{}\n", trim_pattern(line)));
         }
     }
 
@@ -278,17 +1178,60 @@ fn dedup_templates(template: &str, template_vec: &mut [String]) -> Result<String
 fn list_templates(app_conf: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
     // FIXME: Review this function for a better approach if any.
 
+    if app_conf.list_bundles {
+        return list_bundles(app_conf);
+    }
+
     info!("app: listing available templates");
 
+    let template_paths = generate_template_paths(app_conf)?;
+
+    // Group the template identifiers by their directories relative to the repository roots.
+    let mut template_groups = BTreeMap::<String, Vec<String>>::new();
+    for (template, template_files) in template_paths.iter() {
+        for template_file in template_files.iter() {
+            let template_dir = Path::new(&template_file.relative_path)
+                .parent()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let template_group = template_groups.entry(template_dir).or_default();
+            if !template_group.contains(template) {
+                template_group.push(template.to_owned());
+            }
+        }
+    }
+
     let mut template_list = String::new();
-    let mut template_list_line_len = template_list.len();
+    for (template_dir, template_identifiers) in template_groups.iter_mut() {
+        if !template_dir.is_empty() {
+            template_list.push_str(&format!("\n{}/:\n", template_dir));
+        }
 
-    let template_paths = generate_template_paths(app_conf)?;
+        // NOTE: This sort is necessary to achieve a sorted list, unless the `BTreeMap`'s sort is
+        // altered.
+        template_identifiers.sort_by_key(|a| a.to_lowercase());
+        template_list.push_str(&format_template_list(template_identifiers));
+        template_list.push('\n');
+    }
 
-    // NOTE: This sort is necessary to achieve a sorted list, unless the `BTreeMap`'s sort is
-    // altered.
-    let mut template_identifiers: Vec<_> = template_paths.keys().cloned().collect();
-    template_identifiers.sort_by_key(|a| a.to_lowercase());
+    print!("{}", template_list);
+
+    if !app_conf.config.aliases.is_empty() {
+        println!("\nAliases:");
+        for (alias, template) in app_conf.config.aliases.iter() {
+            println!("    {} -> {}", alias, template);
+        }
+    }
+    debug!("app: done listing available templates");
+
+    Ok(())
+}
+
+/// Formats template identifiers into columns, wrapping lines at [`TEMPLATE_LIST_OUTPUT_LIMIT`].
+fn format_template_list(template_identifiers: &[String]) -> String {
+    let mut template_list = String::new();
+    let mut template_list_line_len = template_list.len();
 
     // NOTE: This column print implementation yields the following average `time` results:
     // 0.03s user 0.01s system 99% cpu 0.047 total.
@@ -319,47 +1262,356 @@ fn list_templates(app_conf: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
         }
     }
 
-    println!("{}", template_list);
-    debug!("app: done listing available templates");
+    template_list
+}
+
+/// Lists the configured template bundles & their content.
+fn list_bundles(app_conf: &RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    info!("app: listing template bundles");
+
+    if app_conf.config.bundles.is_empty() {
+        warn!("app: no template bundles configured");
+    }
+
+    for (bundle, templates) in app_conf.config.bundles.iter() {
+        println!("{}{}: {}", BUNDLE_PREFIX, bundle, templates.join(" "));
+    }
+    debug!("app: done listing template bundles");
 
     Ok(())
 }
 
-/// Generates [`TemplatePaths`] for the available gitignore template arguments supplied by a user.
+/// Generates [`SelectedTemplates`] for the available gitignore template arguments supplied by a
+/// user.
 ///
-/// This function generates a [`TemplatePaths`] item for the available gitignore template files
-/// desired by a user.
+/// This function generates a [`SelectedTemplates`] item for the available gitignore template
+/// files desired by a user.
 /// Using the output of [`generate_template_paths`], the [`TemplatePaths`] is filtered to contain
-/// entries explicitly requested by the user.
-fn parse_templates(app_conf: &mut RuntimeConfig) -> Result<TemplatePaths, Box<dyn StdErr>> {
+/// entries explicitly requested by the user (with bundles & aliases expanded by [`expand_bundles`]
+/// & [`expand_aliases`]), as selected by [`select_templates`].
+/// The templates retain the order of the user's request, unless [`RuntimeConfig::alpha_sort`] is
+/// set.
+/// Unmatched template arguments are reported alongside suggestions from
+/// [`suggest_template_names`], failing should no argument match.
+/// The matched template arguments are returned too, as recorded in a [`Lockfile`].
+fn parse_templates(
+    app_conf: &RuntimeConfig,
+    template_paths: &TemplatePaths,
+) -> Result<(SelectedTemplates, Vec<String>), Box<dyn StdErr>> {
     debug!("app: parsing template options");
 
-    let template_list = app_conf.templates.clone();
+    let template_list = expand_aliases(
+        &expand_bundles(&app_conf.templates, &app_conf.config.bundles)?,
+        &app_conf.config.aliases,
+    );
 
-    let mut available_templates = TemplatePaths::new();
-    let template_paths = generate_template_paths(app_conf)?;
+    let (available_templates, missing_templates) = select_templates(
+        &template_list,
+        template_paths,
+        &app_conf.config.repository.config,
+        app_conf.alpha_sort,
+    );
+
+    let template_arguments = template_list
+        .iter()
+        .filter(|template| {
+            !missing_templates
+                .iter()
+                .any(|(missing_template, _)| missing_template == *template)
+        })
+        .cloned()
+        .collect();
+
+    if !missing_templates.is_empty() {
+        let missing_message = missing_templates
+            .iter()
+            .map(|(template, reason)| format!("`{}`{}", template, reason))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if available_templates.is_empty() {
+            return Err(Box::new(Error::with_message(
+                ErrorKind::MissingTemplates,
+                missing_message,
+            )));
+        }
+        warn!("app: could not locate template(s) {}", missing_message);
+    }
+
+    debug!("app: selected available template options");
+
+    Ok((available_templates, template_arguments))
+}
+
+/// Selects the templates resolved from a list of template arguments, alongside the unmatched
+/// arguments & the reason for their failure.
+///
+/// This function merges the files of arguments resolving to the same template, the templates
+/// retaining the order of their first argument unless sorted alphabetically by `alpha_sort`.
+fn select_templates(
+    template_list: &[String],
+    template_paths: &TemplatePaths,
+    repos: &[RepoConfig],
+    alpha_sort: bool,
+) -> (SelectedTemplates, Vec<(String, String)>) {
+    let mut available_templates = SelectedTemplates::new();
+    let mut missing_templates = Vec::<(String, String)>::new();
 
     for template in template_list {
-        // NOTE: The `clippy::option_map_unit_fn` warning was thrown for using a `map` on the below
-        // operation.
-        //
-        // Using `if let` is preferred for readability when a function doesn't return anything
-        // meaningful: `std::unit`/`()`.
-        if let Some(t_paths) = template_paths.get(&template) {
-            *available_templates.entry(template).or_default() = t_paths.to_vec();
+        let (template_key, template_files) = match resolve_template(template, template_paths, repos)
+        {
+            Ok(resolution) => resolution,
+            Err(reason) => {
+                missing_templates.push((template.to_owned(), reason));
+                continue;
+            }
+        };
+
+        let index = match available_templates
+            .iter()
+            .position(|(available_template, _)| *available_template == template_key)
+        {
+            Some(index) => index,
+            None => {
+                available_templates.push((template_key, Vec::new()));
+                available_templates.len() - 1
+            }
         };
+
+        let t_paths = &mut available_templates[index].1;
+        for template_file in template_files {
+            if !t_paths.contains(&template_file) {
+                t_paths.push(template_file);
+            }
+        }
     }
 
-    debug!("app: selected available template options");
+    if alpha_sort {
+        available_templates
+            .sort_by(|(template, _), (other_template, _)| template.cmp(other_template));
+    }
 
-    Ok(available_templates)
+    (available_templates, missing_templates)
+}
+
+/// Resolves a template argument to its identifier & files.
+///
+/// A template argument qualified by a repository (see [`split_repo_qualifier`]) only matches that
+/// repository's files.
+/// The files are selected by [`select_template_files`], the reason for the failure is returned
+/// otherwise.
+fn resolve_template(
+    template: &str,
+    template_paths: &TemplatePaths,
+    repos: &[RepoConfig],
+) -> Result<(String, Vec<TemplateFile>), String> {
+    let (qualifier, template_name) = split_repo_qualifier(template);
+
+    let qualifier = match qualifier {
+        Some(qualifier) => qualifier,
+        None => return select_template_files(template_name, template_paths, repos),
+    };
+
+    let qualified_paths = qualify_template_paths(template_paths, qualifier, repos)?;
+    if qualified_paths.is_empty() {
+        return Err(format!(" (unknown template source `{}`)", qualifier));
+    }
+
+    let (matched_template, template_files) =
+        select_template_files(template_name, &qualified_paths, repos)?;
+
+    Ok((
+        format!(
+            "{}{}{}",
+            qualifier, REPO_QUALIFIER_DELIMITER, matched_template
+        ),
+        template_files,
+    ))
+}
+
+/// Selects the template files matching a template name.
+///
+/// This function matches a template path (containing a `/`) against the template files' relative
+/// paths as described in [`match_template_path`], otherwise the template name as described in
+/// [`match_template_names`].
+/// The matched files from the repositories with the highest [`RepoConfig::priority`] are selected
+/// (see [`prioritize_template_files`]), those of a template name exactly matching the user's
+/// being preferred among them.
+/// A template name matching files from different directories of a repository is ambiguous.
+/// The matched template identifier & files are returned, otherwise the reason for the failure.
+fn select_template_files(
+    template_name: &str,
+    template_paths: &TemplatePaths,
+    repos: &[RepoConfig],
+) -> Result<(String, Vec<TemplateFile>), String> {
+    use std::collections::BTreeSet;
+
+    if template_name.contains('/') {
+        let template_files = prioritize_template_files(
+            template_paths
+                .values()
+                .flatten()
+                .filter(|template_file| {
+                    match_template_path(template_name, &template_file.template_path())
+                })
+                .cloned()
+                .collect(),
+            repos,
+        );
+
+        let template_file_paths: BTreeSet<String> = template_files
+            .iter()
+            .map(|template_file| template_file.template_path())
+            .collect();
+
+        return match template_file_paths.len() {
+            0 => Err("".to_owned()),
+            1 => Ok((
+                template_file_paths.into_iter().next().unwrap(),
+                template_files,
+            )),
+            _ => Err(format!(
+                " (ambiguous, did you mean: {}?)",
+                template_file_paths
+                    .into_iter()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+    }
+
+    let matched_templates = match_template_names(template_name, template_paths.keys());
+    if matched_templates.is_empty() {
+        let suggestions = suggest_template_names(template_name, template_paths.keys());
+        if suggestions.is_empty() {
+            return Err("".to_owned());
+        }
+
+        return Err(format!(" (did you mean: {}?)", suggestions.join(", ")));
+    }
+
+    // NOTE: Templates matched under normalization are merged into the first remaining match.
+    let mut template_files = Vec::new();
+    for matched_template in matched_templates.iter() {
+        template_files.extend(template_paths[*matched_template].iter().cloned());
+    }
+    let mut template_files = prioritize_template_files(template_files, repos);
+
+    let template_name_of =
+        |template_file: &TemplateFile| remove_filetype(Path::new(&template_file.relative_path));
+    if template_files
+        .iter()
+        .any(|template_file| template_name_of(template_file) == template_name)
+    {
+        template_files.retain(|template_file| template_name_of(template_file) == template_name);
+    }
+
+    let mut repo_template_dirs = BTreeMap::<&str, BTreeSet<Option<&Path>>>::new();
+    for template_file in template_files.iter() {
+        repo_template_dirs
+            .entry(&template_file.repo)
+            .or_default()
+            .insert(Path::new(&template_file.relative_path).parent());
+    }
+    let ambiguous = repo_template_dirs
+        .values()
+        .any(|template_dirs| template_dirs.len().gt(&1));
+
+    if ambiguous {
+        let template_file_paths: BTreeSet<String> = template_files
+            .iter()
+            .map(|template_file| template_file.anchored_template_path())
+            .collect();
+
+        return Err(format!(
+            " (ambiguous, did you mean: {}?)",
+            template_file_paths
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    Ok((template_name_of(&template_files[0]), template_files))
+}
+
+/// Filters a [`TemplatePaths`] item to the files of the repositories matching a qualifier.
+///
+/// This function retains the template files from the repository matched by [`match_repository`],
+/// dropping the templates left without files.
+/// A qualifier matching several repositories is ambiguous, the reason for the failure is returned.
+fn qualify_template_paths(
+    template_paths: &TemplatePaths,
+    qualifier: &str,
+    repos: &[RepoConfig],
+) -> Result<TemplatePaths, String> {
+    let qualified_repos: Vec<&str> = repos
+        .iter()
+        .filter(|repo| !repo.skip && match_repository(qualifier, repo))
+        .map(|repo| repo.path.as_str())
+        .collect();
+
+    if qualified_repos.len() > 1 {
+        return Err(format!(
+            " (ambiguous template source `{}`, did you mean: {}?)",
+            qualifier,
+            qualified_repos
+                .iter()
+                .map(|repo| format!("{}{}", repo, REPO_QUALIFIER_DELIMITER))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    Ok(template_paths
+        .iter()
+        .filter_map(|(template, template_files)| {
+            let qualified_files: Vec<TemplateFile> = template_files
+                .iter()
+                .filter(|template_file| qualified_repos.contains(&template_file.repo.as_str()))
+                .cloned()
+                .collect();
+
+            (!qualified_files.is_empty()).then(|| (template.to_owned(), qualified_files))
+        })
+        .collect())
+}
+
+/// Selects the template files from the repositories with the highest [`RepoConfig::priority`].
+///
+/// Files from repositories sharing the highest priority are all retained.
+fn prioritize_template_files(
+    template_files: Vec<TemplateFile>,
+    repos: &[RepoConfig],
+) -> Vec<TemplateFile> {
+    let repo_priority = |template_file: &TemplateFile| {
+        repos
+            .iter()
+            .find(|repo| repo.path == template_file.repo)
+            .map(|repo| repo.priority)
+            .unwrap_or_default()
+    };
+
+    let highest_priority = match template_files.iter().map(repo_priority).max() {
+        Some(highest_priority) => highest_priority,
+        None => return template_files,
+    };
+
+    template_files
+        .into_iter()
+        .filter(|template_file| repo_priority(template_file) == highest_priority)
+        .collect()
 }
 
 /// Populates a [`TemplatePaths`] item with filepath entries.
 ///
-/// This function recurses on the content of the cached gitignore template repositories, appending
+/// This function recurses on the content of a cached gitignore template repository, appending
 /// filepath entries to the passed [`TemplatePaths`] item for all available templates.
-fn update_template_paths(dir: &Path, template_paths: &mut TemplatePaths) -> io::Result<()> {
+fn update_template_paths(
+    repo: &RepoConfig,
+    repo_dir: &Path,
+    dir: &Path,
+    template_paths: &mut TemplatePaths,
+) -> io::Result<()> {
     debug!("app: updating template file paths for {}", dir.display());
 
     // Store template name & path in hashmap.
@@ -374,7 +1626,7 @@ fn update_template_paths(dir: &Path, template_paths: &mut TemplatePaths) -> io::
         let entry_path_string = entry_path.clone().into_os_string().into_string().unwrap();
 
         if entry_path.is_dir() {
-            update_template_paths(&entry_path, template_paths)?;
+            update_template_paths(repo, repo_dir, &entry_path, template_paths)?;
             debug!("app: template scan directory {}", &entry_path_string);
 
             continue;
@@ -384,7 +1636,17 @@ fn update_template_paths(dir: &Path, template_paths: &mut TemplatePaths) -> io::
             .entry(remove_filetype(&entry.path()))
             .or_default();
 
-        template.push(entry_path_string);
+        template.push(TemplateFile {
+            repo: repo.path.to_owned(),
+            relative_path: entry_path
+                .strip_prefix(repo_dir)
+                .unwrap_or(&entry_path)
+                .to_string_lossy()
+                .into_owned(),
+            path: entry_path_string,
+            commit: None,
+            hash: None,
+        });
     }
 
     debug!(
@@ -417,7 +1679,8 @@ fn generate_template_paths(app_conf: &mut RuntimeConfig) -> Result<TemplatePaths
             }
         };
 
-        update_template_paths(Path::new(&absolute_repo_path), &mut template_paths)?;
+        let repo_dir = Path::new(&absolute_repo_path);
+        update_template_paths(conf, repo_dir, repo_dir, &mut template_paths)?;
     }
     debug!("app: template hash map {:#?}", template_paths);
 
@@ -455,3 +1718,212 @@ fn ignore_file(entry: &DirEntry) -> bool {
         .unwrap_or(false)
         || is_hidden(entry)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managed::{MANAGED_BLOCK_END, MANAGED_BLOCK_START};
+
+    #[test]
+    /// Assert supplementary template content is compared as normalized rules.
+    fn dedup_templates_test() {
+        let mut template_vec = vec![
+            "*.obj\n*.old\n**/node_modules/\n".to_owned(),
+            "# Objects\n*.o\n*.obj\nThumbs.db\nnode_modules/\n!keep.o\n".to_owned(),
+        ];
+
+        assert_eq!(
+            dedup_templates("C", &mut template_vec).unwrap(),
+            format!(
+                "{}\n# C supplementary content\n{}\n*.o\nThumbs.db\n!keep.o\n{}\n",
+                template_vec[0], TEMPLATE_SUPPLEMENT_DELIMITER, TEMPLATE_SUPPLEMENT_DELIMITER
+            )
+        );
+    }
+
+    #[test]
+    /// Assert templates are selected in request order, or alphabetically should it be requested.
+    fn select_templates_test() {
+        let template_paths: TemplatePaths = ["Node", "Rust", "Yarn"]
+            .iter()
+            .map(|template| {
+                (
+                    template.to_string(),
+                    vec![TemplateFile {
+                        repo: "/cache/a".to_owned(),
+                        relative_path: format!("{}.gitignore", template),
+                        path: format!("/cache/a/{}.gitignore", template),
+                        commit: None,
+                        hash: None,
+                    }],
+                )
+            })
+            .collect();
+        let template_list: Vec<String> = ["yarn", "Rust", "Missing", "Node", "Yarn"]
+            .iter()
+            .map(|template| template.to_string())
+            .collect();
+
+        let selected_names = |alpha_sort| {
+            let (selected_templates, missing_templates) =
+                select_templates(&template_list, &template_paths, &[], alpha_sort);
+            assert_eq!(
+                missing_templates,
+                vec![("Missing".to_owned(), "".to_owned())]
+            );
+
+            selected_templates
+                .into_iter()
+                .map(|(template, template_files)| {
+                    assert_eq!(template_files.len(), 1);
+                    template
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(selected_names(false), vec!["Yarn", "Rust", "Node"]);
+        assert_eq!(selected_names(true), vec!["Node", "Rust", "Yarn"]);
+    }
+
+    #[test]
+    /// Assert repository priority prevails over exact template name matches, which break ties.
+    fn resolve_template_test() {
+        let template_file = |repo: &str, template: &str| TemplateFile {
+            repo: repo.to_owned(),
+            relative_path: format!("{}.gitignore", template),
+            path: format!("/cache/{}/{}.gitignore", repo, template),
+            commit: None,
+            hash: None,
+        };
+        let template_paths: TemplatePaths = vec![
+            ("Rust".to_owned(), vec![template_file("github", "Rust")]),
+            ("rust".to_owned(), vec![template_file("toptal", "rust")]),
+        ]
+        .into_iter()
+        .collect();
+        let repo = |path: &str, priority| RepoConfig {
+            auto_update: false,
+            skip: false,
+            path: path.to_owned(),
+            url: String::new(),
+            priority,
+        };
+
+        let prioritized_repos = vec![repo("github", 1), repo("toptal", 0)];
+        for template in &["rust", "Rust", "RUST"] {
+            assert_eq!(
+                resolve_template(template, &template_paths, &prioritized_repos),
+                Ok(("Rust".to_owned(), vec![template_file("github", "Rust")]))
+            );
+        }
+
+        let equal_repos = vec![repo("github", 0), repo("toptal", 0)];
+        assert_eq!(
+            resolve_template("rust", &template_paths, &equal_repos),
+            Ok(("rust".to_owned(), vec![template_file("toptal", "rust")]))
+        );
+        assert_eq!(
+            resolve_template("RUST", &template_paths, &equal_repos),
+            Ok((
+                "Rust".to_owned(),
+                vec![
+                    template_file("github", "Rust"),
+                    template_file("toptal", "rust")
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    /// Assert the generated content of a gitignore lacking a managed block is enclosed within one,
+    /// from its header to its last section.
+    fn enclose_generated_content_test() {
+        let header = "#\n# .gitignore\n#\n\n# Templates used: {{templates}}\n";
+        let generated = "#\n# .gitignore\n#\n\n# Templates used: Node\n\n# Node\n# ----\n\
+            node_modules/\n# ----\n";
+        let gitignore = format!("/local\n{}*.secret\n", generated);
+
+        let enclosed = enclose_generated_content(&gitignore, header);
+        assert_eq!(
+            enclosed,
+            format!("/local\n{}*.secret\n", wrap_managed_block(generated))
+        );
+        assert_eq!(enclose_generated_content(&enclosed, header), enclosed);
+        assert_eq!(enclose_generated_content("target/\n", header), "target/\n");
+
+        let regenerated = "# Templates used: Node\n\n# Node\n# ----\nnode_modules/\n# ----\n";
+        assert_eq!(
+            merge_managed_block(&enclosed, regenerated).unwrap(),
+            format!("/local\n{}*.secret\n", wrap_managed_block(regenerated))
+        );
+    }
+
+    #[test]
+    /// Assert a locked gitignore is checked as up to date & regenerated byte-identically, after
+    /// its template changes upstream too.
+    fn locked_regeneration_test() {
+        use crate::git::{commit_file, init_repository};
+
+        let root = std::env::temp_dir().join(format!("ignore-locked-{}", std::process::id()));
+        let repo_dir = root.join("cache/local");
+        fs::create_dir_all(&repo_dir).unwrap();
+        let repo = init_repository(&repo_dir).unwrap();
+        let commit_template = |content: &str| {
+            fs::write(repo_dir.join("Node.gitignore"), content).unwrap();
+            commit_file(&repo, Path::new("Node.gitignore"), "Update Node").unwrap();
+        };
+        commit_template("node_modules/\n");
+
+        let output_file = root.join("gitignore").to_string_lossy().into_owned();
+        let app_confg = || {
+            let mut app_confg = RuntimeConfig::default();
+            app_confg.config.repository.cache_dir = root.join("cache").to_string_lossy().into();
+            app_confg.config.repository.config = vec![RepoConfig {
+                auto_update: false,
+                skip: false,
+                path: "local".to_owned(),
+                url: String::new(),
+                priority: 0,
+            }];
+            app_confg.config.output.header =
+                "# Templates used: {{templates}}\n{{sources}}\n".into();
+            app_confg.gitignore_output_file = output_file.to_owned();
+            app_confg.templates = vec!["node".to_owned()];
+            app_confg.compact = true;
+            app_confg
+        };
+
+        generate_gitignore(&mut app_confg()).unwrap();
+        let generated = fs::read_to_string(&output_file).unwrap();
+        check_gitignore(&mut app_confg()).unwrap();
+
+        commit_template("node_modules/\n*.log\n");
+        let check_result = check_gitignore(&mut app_confg());
+
+        let mut locked_confg = app_confg();
+        locked_confg.templates.clear();
+        locked_confg.compact = false;
+        locked_confg.locked = true;
+        generate_gitignore(&mut locked_confg).unwrap();
+        let regenerated = fs::read_to_string(&output_file).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(check_result.is_err());
+        assert_eq!(regenerated, generated);
+    }
+
+    #[test]
+    /// Assert the templates listed within the managed block are obtained.
+    fn templates_used_test() {
+        let gitignore = format!(
+            "# Templates used: Ignored\n{}\n#\n# .gitignore\n#\n\n# Templates used: Rust local/a:Node\n{}\n",
+            MANAGED_BLOCK_START, MANAGED_BLOCK_END
+        );
+
+        assert_eq!(
+            templates_used(&gitignore),
+            Some(vec!["Rust".to_owned(), "local/a:Node".to_owned()])
+        );
+        assert_eq!(templates_used("target/\n"), None);
+    }
+}
@@ -3,9 +3,10 @@
 //! The `cli` module defines functions necessary for the setup of [`clap`] and [`fern`].
 
 use std::error::Error as StdErr;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
 use clap::{Arg, ArgAction, Command};
 use clap_complete::Shell;
 
@@ -13,12 +14,39 @@ use crate::errors::Error;
 use crate::errors::ErrorKind;
 
 pub const DEFAULT_OUTPUT_FILE: &str = "gitignore";
+pub const STDOUT_OUTPUT_FILE: &str = "-";
 const DEFAULT_CONFIG_PATH: &str = "ignore/config.toml";
 
 pub const COMPLETIONS_SUBCMD: &str = "completions";
 pub const LIST_SUBCMD: &str = "list";
 pub const UPDATE_SUBCMD: &str = "update";
 pub const GENERATE_SUBCMD: &str = "generate";
+pub const CHECK_SUBCMD: &str = "check";
+pub const DIFF_SUBCMD: &str = "diff";
+pub const REGENERATE_SUBCMD: &str = "regenerate";
+pub const ADD_SUBCMD: &str = "add";
+pub const REMOVE_SUBCMD: &str = "remove";
+pub const TEMPLATE_SUBCMD: &str = "template";
+pub const TEMPLATE_NEW_SUBCMD: &str = "new";
+pub const TEMPLATE_EDIT_SUBCMD: &str = "edit";
+pub const TEMPLATE_SAVE_SUBCMD: &str = "save";
+
+pub const OUTPUT_TARGET_FILE: &str = "file";
+pub const OUTPUT_TARGET_EXCLUDE: &str = "exclude";
+pub const OUTPUT_TARGET_GLOBAL: &str = "global";
+
+pub const OUTPUT_FORMAT_GITIGNORE: &str = "gitignore";
+pub const OUTPUT_FORMAT_DOCKERIGNORE: &str = "dockerignore";
+pub const OUTPUT_FORMAT_HGIGNORE: &str = "hgignore";
+
+pub const ANNOTATE_SECTION: &str = "section";
+pub const ANNOTATE_LINE: &str = "line";
+
+pub const SORT_REQUEST: &str = "request";
+pub const SORT_ALPHA: &str = "alpha";
+
+pub const DEFAULT_TEMPLATE_SOURCE: &str = "local/templates";
+pub const DEFAULT_CAPTURE_FILE: &str = ".gitignore";
 
 lazy_static! {
     static ref CFG_FILE_PATH_BUF: PathBuf = {
@@ -32,6 +60,50 @@ lazy_static! {
     static ref CFG_FILE: &'static str = CFG_FILE_PATH_BUF.to_str().unwrap_or(DEFAULT_CONFIG_PATH);
 }
 
+/// [`clap::builder::TypedValueParser`] accepting any template name while offering the known names
+/// (i.e. aliases & bundles) as shell completion candidates.
+#[derive(Clone, Debug, Default)]
+pub struct TemplateValueParser {
+    /// Template names offered as completion candidates.
+    completion_names: Vec<String>,
+}
+
+/// [`clap::builder::TypedValueParser`] trait implementation for [`TemplateValueParser`].
+impl TypedValueParser for TemplateValueParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        if self.completion_names.is_empty() {
+            return None;
+        }
+
+        Some(Box::new(
+            self.completion_names
+                .iter()
+                .map(|name| PossibleValue::new(name.to_owned())),
+        ))
+    }
+}
+
+/// Parses a template variable assignment of the form `KEY=VALUE`.
+fn parse_template_variable(assignment: &str) -> Result<(String, String), String> {
+    match assignment.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_owned(), value.to_owned()))
+        }
+        _ => Err(format!("expected KEY=VALUE, found `{}`", assignment)),
+    }
+}
+
 /// Obtains the default config file path for the executable's operating system.
 #[allow(dead_code)]
 pub fn get_config_file_path() -> Result<OsString, Box<dyn StdErr>> {
@@ -46,8 +118,11 @@ pub fn get_config_file_path() -> Result<OsString, Box<dyn StdErr>> {
 }
 
 /// Builds a [`clap::Command`].
+///
+/// The template completion names are offered as completion candidates for the `template`
+/// argument.
 // pub fn build_cli() -> Result<Command<'static>, Box<dyn StdErr>> {
-pub fn build_cli() -> Command {
+pub fn build_cli(template_completion_names: Vec<String>) -> Command {
     Command::new(crate_name!())
         .arg_required_else_help(true)
         .version(crate_version!())
@@ -87,6 +162,12 @@ pub fn build_cli() -> Command {
         .subcommand(
             Command::new(LIST_SUBCMD)
             .about("List available languages, tools & projects")
+            .arg(
+                Arg::new("bundles")
+                .help("List the configured template bundles instead")
+                .long("bundles")
+                .action(ArgAction::SetTrue)
+            )
         )
         .subcommand(
             Command::new(GENERATE_SUBCMD)
@@ -94,7 +175,7 @@ pub fn build_cli() -> Command {
             .about("Generate gitignore file")
             .arg(
                 Arg::new("output")
-                .help("Specify output FILE")
+                .help("Specify output FILE, `-` for stdout")
                 .default_value(DEFAULT_OUTPUT_FILE)
                 .short('o')
                 .long("output")
@@ -103,12 +184,292 @@ pub fn build_cli() -> Command {
             )
             .arg(
                 Arg::new("template")
-                .help("Case sensitive (space-separated) list of TEMPLATE(s) to use in generating the gitignore file")
+                .help("Case insensitive (space-separated) list of [REPO:][DIR/]TEMPLATE(s) or @BUNDLE(s) to use in generating the gitignore file")
                 .short('t')
                 .long("templates")
                 .num_args(1..)
                 .value_name("TEMPLATE")
+                .value_parser(TemplateValueParser {
+                    completion_names: template_completion_names.clone(),
+                })
+                .hide_possible_values(true)
+                .action(ArgAction::Append)
+            )
+            .arg(
+                Arg::new("stdout")
+                .help("Write the generated gitignore to stdout, same as `--output -`")
+                .long("stdout")
+                .conflicts_with("output")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("target")
+                .help("Write to the output FILE, the repository's `.git/info/exclude` or the global `core.excludesFile`")
+                .long("target")
+                .value_name("TARGET")
+                .default_value(OUTPUT_TARGET_FILE)
+                .value_parser([OUTPUT_TARGET_FILE, OUTPUT_TARGET_EXCLUDE, OUTPUT_TARGET_GLOBAL])
+                .conflicts_with_all(["output", "stdout"])
+            )
+            .arg(
+                Arg::new("sort")
+                .help("Order the TEMPLATE(s) sections as requested or alphabetically")
+                .long("sort")
+                .value_name("ORDER")
+                .default_value(SORT_REQUEST)
+                .value_parser([SORT_REQUEST, SORT_ALPHA])
+            )
+            .arg(
+                Arg::new("annotate")
+                .help("Annotate each section or rule with the template file (& line) it stems from")
+                .long("annotate")
+                .value_name("LEVEL")
+                .value_parser([ANNOTATE_SECTION, ANNOTATE_LINE])
+            )
+            .arg(
+                Arg::new("format")
+                .help("Convert the generated rules to the FORMAT's ignore file semantics")
+                .long("format")
+                .value_name("FORMAT")
+                .default_value(OUTPUT_FORMAT_GITIGNORE)
+                .value_parser([OUTPUT_FORMAT_GITIGNORE, OUTPUT_FORMAT_DOCKERIGNORE, OUTPUT_FORMAT_HGIGNORE])
+            )
+            .arg(
+                Arg::new("detect")
+                .help("Detect the TEMPLATE(s) to use from the project working tree in DIR")
+                .long("detect")
+                .value_name("DIR")
+                .num_args(0..=1)
+                .default_missing_value(".")
+                .value_parser(value_parser!(PathBuf))
+            )
+            .arg(
+                Arg::new("dry_run")
+                .help("Print the changes to the output FILE as a unified diff without writing it")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("global_dedup")
+                .help("Deduplicate patterns repeated across TEMPLATE(s), noting the first occurrence")
+                .long("global-dedup")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("strip_comments")
+                .help("Drop the comment lines of TEMPLATE(s), keeping section headers")
+                .long("strip-comments")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("compact")
+                .help("Drop the blank lines of TEMPLATE(s) & between sections")
+                .long("compact")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("normalize")
+                .help("Trim trailing whitespace & unify line endings of TEMPLATE(s)")
+                .long("normalize")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("check_conflicts")
+                .help("Report negations & patterns of TEMPLATE(s) overriding those of other TEMPLATE(s)")
+                .long("check-conflicts")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("deny_conflicts")
+                .help("Fail on conflicting TEMPLATE(s) rules, implies --check-conflicts")
+                .long("deny-conflicts")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("set")
+                .help("Set a template preprocessor variable, overriding the detected project variables")
+                .long("set")
+                .value_name("KEY=VALUE")
+                .value_parser(parse_template_variable)
                 .action(ArgAction::Append)
             )
+            .arg(
+                Arg::new("locked")
+                .help("Regenerate the gitignore from the template files & options recorded in the output FILE's lockfile")
+                .long("locked")
+                .conflicts_with_all([
+                    "template", "detect", "sort", "annotate", "format", "global_dedup", "strip_comments",
+                    "compact", "normalize", "set",
+                ])
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("force_overwrite")
+                .help("Overwrite the output FILE instead of merging into its managed block")
+                .short('f')
+                .long("force-overwrite")
+                .action(ArgAction::SetTrue)
+            )
+        )
+        .subcommand(
+            Command::new(CHECK_SUBCMD)
+            .about("Check a generated gitignore file for drift from its templates, failing on drift")
+            .arg(
+                Arg::new("file")
+                .help("Specify the gitignore FILE to check")
+                .default_value(DEFAULT_OUTPUT_FILE)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+            )
+        )
+        .subcommand(
+            Command::new(DIFF_SUBCMD)
+            .about("Preview the regeneration of a generated gitignore file as a unified diff")
+            .arg(
+                Arg::new("file")
+                .help("Specify the gitignore FILE to diff")
+                .default_value(DEFAULT_OUTPUT_FILE)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+            )
+        )
+        .subcommand(
+            Command::new(REGENERATE_SUBCMD)
+            .about("Regenerate a generated gitignore file from its recorded templates")
+            .arg(
+                Arg::new("file")
+                .help("Specify the gitignore FILE to regenerate")
+                .default_value(DEFAULT_OUTPUT_FILE)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+            )
+            .arg(
+                Arg::new("dry_run")
+                .help("Print the changes to the FILE as a unified diff without writing it")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+            )
+        )
+        .subcommand(
+            Command::new(ADD_SUBCMD)
+            .arg_required_else_help(true)
+            .about("Add template(s) to a generated gitignore file")
+            .arg(
+                Arg::new("template")
+                .help("Case insensitive (space-separated) list of [REPO:][DIR/]TEMPLATE(s) or @BUNDLE(s) to add")
+                .required(true)
+                .num_args(1..)
+                .value_name("TEMPLATE")
+                .value_parser(TemplateValueParser {
+                    completion_names: template_completion_names.clone(),
+                })
+                .hide_possible_values(true)
+            )
+            .arg(
+                Arg::new("output")
+                .help("Specify the gitignore FILE to update")
+                .default_value(DEFAULT_OUTPUT_FILE)
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+            )
+            .arg(
+                Arg::new("dry_run")
+                .help("Print the changes to the FILE as a unified diff without writing it")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+            )
+        )
+        .subcommand(
+            Command::new(REMOVE_SUBCMD)
+            .arg_required_else_help(true)
+            .about("Remove template(s) from a generated gitignore file")
+            .arg(
+                Arg::new("template")
+                .help("Case insensitive (space-separated) list of [REPO:][DIR/]TEMPLATE(s) or @BUNDLE(s) to remove")
+                .required(true)
+                .num_args(1..)
+                .value_name("TEMPLATE")
+                .value_parser(TemplateValueParser {
+                    completion_names: template_completion_names,
+                })
+                .hide_possible_values(true)
+            )
+            .arg(
+                Arg::new("output")
+                .help("Specify the gitignore FILE to update")
+                .default_value(DEFAULT_OUTPUT_FILE)
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+            )
+            .arg(
+                Arg::new("dry_run")
+                .help("Print the changes to the FILE as a unified diff without writing it")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+            )
+        )
+        .subcommand(
+            Command::new(TEMPLATE_SUBCMD)
+            .arg_required_else_help(true)
+            .about("Author custom templates in a local template source")
+            .arg(
+                Arg::new("source")
+                .help("Specify the local template source's PATH, relative to the repository cache directory")
+                .long("source")
+                .value_name("PATH")
+                .default_value(DEFAULT_TEMPLATE_SOURCE)
+                .global(true)
+                .value_parser(value_parser!(String))
+            )
+            .arg(
+                Arg::new("git")
+                .help("Back the local template source with a git repository, committing template changes")
+                .long("git")
+                .global(true)
+                .action(ArgAction::SetTrue)
+            )
+            .subcommand(
+                Command::new(TEMPLATE_NEW_SUBCMD)
+                .about("Create a template & open it in $EDITOR")
+                .arg(
+                    Arg::new("name")
+                    .help("Template NAME, may include a relative directory")
+                    .value_name("NAME")
+                    .required(true)
+                )
+            )
+            .subcommand(
+                Command::new(TEMPLATE_EDIT_SUBCMD)
+                .about("Open an existing template in $EDITOR")
+                .arg(
+                    Arg::new("name")
+                    .help("Template NAME, may include a relative directory")
+                    .value_name("NAME")
+                    .required(true)
+                )
+            )
+            .subcommand(
+                Command::new(TEMPLATE_SAVE_SUBCMD)
+                .about("Capture an existing gitignore FILE as a template")
+                .arg(
+                    Arg::new("name")
+                    .help("Template NAME, may include a relative directory")
+                    .value_name("NAME")
+                    .required(true)
+                )
+                .arg(
+                    Arg::new("from")
+                    .help("Specify the gitignore FILE to capture")
+                    .short('f')
+                    .long("from")
+                    .value_name("FILE")
+                    .default_value(DEFAULT_CAPTURE_FILE)
+                    .value_parser(value_parser!(PathBuf))
+                )
+            )
         )
 }
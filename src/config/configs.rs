@@ -3,6 +3,7 @@
 //! The `config` module defines elements necessary for the setup and configuration of [`Config`]
 //! (part of runtime environment).
 
+use std::collections::btree_map::BTreeMap;
 use std::error::Error as StdErr;
 use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
@@ -19,6 +20,9 @@ const GITIGNORE_DEFAULT_REPO: &str = "https://github.com/github/gitignore";
 /// storing gitignore template repositories--.
 const GITIGNORE_REPO_CACHE_DIR: &str = "ignore/repos";
 
+/// Constant specifying the default header of a generated gitignore file.
+const GITIGNORE_DEFAULT_HEADER: &str = "#\n# .gitignore\n#\n\n# Templates used: {{templates}}\n";
+
 /// `struct` containing the runtime options loaded from a config file.
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 #[serde(default)]
@@ -29,6 +33,48 @@ pub struct Config {
 
     /// Repository specific configuration options.
     pub repository: BaseRepoConfig,
+
+    /// Template aliases, mapping short names to the template names they expand to.
+    pub aliases: BTreeMap<String, String>,
+
+    /// Template bundles, mapping bundle names to the template (or `@`bundle) names they expand to.
+    pub bundles: BTreeMap<String, Vec<String>>,
+
+    /// Generated gitignore file options, omitted from the config file while defaulted so changes
+    /// to the default header reach the user.
+    #[serde(skip_serializing_if = "OutputConfig::is_default")]
+    pub output: OutputConfig,
+}
+
+/// `struct` containing the config file's generated gitignore file options.
+///
+/// The header & footer are preprocessed like templates, with the `version`, `time`, `command`,
+/// `templates` & `sources` variables available alongside the template variables.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Content preceding the templates.
+    pub header: String,
+
+    /// Content following the templates.
+    pub footer: String,
+}
+
+/// Method implementations for [`OutputConfig`].
+impl OutputConfig {
+    /// Checks whether the options hold their default values.
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            header: GITIGNORE_DEFAULT_HEADER.to_owned(),
+            footer: "".to_owned(),
+        }
+    }
 }
 
 /// `struct` containing the config file's common repository options and an array of repository
@@ -56,6 +102,11 @@ pub struct RepoConfig {
 
     /// URL of git repository containing gitignore templates.
     pub url: String,
+
+    /// Precedence of the repository's templates over same-named templates from other
+    /// repositories, the highest wins when the template name lacks a repository qualifier.
+    #[serde(default)]
+    pub priority: i64,
 }
 
 impl Default for Config {
@@ -108,8 +159,12 @@ impl Default for Config {
                     skip: false,
                     url: default_gitignore_repo,
                     path: r_path,
+                    priority: 0,
                 }],
             },
+            aliases: BTreeMap::new(),
+            bundles: BTreeMap::new(),
+            output: OutputConfig::default(),
         }
     }
 }
@@ -188,6 +243,26 @@ impl Config {
 
         self.update_file(&mut config_file)
     }
+
+    /// Appends a repository's [`RepoConfig`] to the config file, as a `[[repository.config]]`
+    /// entry.
+    ///
+    /// Unlike [`Config::save_file`], the rest of the config file (comments included) is left as
+    /// is.
+    pub fn append_repository(&mut self, repo: RepoConfig) -> Result<(), Box<dyn StdErr>> {
+        debug!("config: file appending repository {}", repo.path);
+
+        let mut config_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config_path)?;
+        config_file.write_all(
+            format!("\n[[repository.config]]\n{}", toml::to_string(&repo)?).as_bytes(),
+        )?;
+        self.repository.config.push(repo);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -226,8 +301,15 @@ mod tests {
                     skip: false,
                     url: GITIGNORE_DEFAULT_REPO.to_owned(),
                     path: "github/gitignore".to_owned(),
+                    priority: 0,
                 }],
             },
+            aliases: BTreeMap::new(),
+            bundles: BTreeMap::new(),
+            output: OutputConfig {
+                header: GITIGNORE_DEFAULT_HEADER.to_owned(),
+                footer: "".to_owned(),
+            },
         };
 
         assert!(test_config.eq(&config));
@@ -8,6 +8,7 @@ use clap::ArgMatches;
 ///
 /// This function configures the logger to output log messages using the `ISO` date format and
 /// verbosity levels specified by the verbosity arguments (within [`clap::ArgMatches`]).
+/// Log messages are written to stderr, leaving stdout for the generated output.
 ///
 /// The arguments set the output verbosity for this crate to a maximum log level of either:
 /// [`log::LevelFilter::Info`], [`log::LevelFilter::Debug`], [`log::LevelFilter::Trace`],
@@ -28,7 +29,7 @@ pub fn setup_logger(matches: &ArgMatches) -> Result<(), fern::InitError> {
         1 => LevelFilter::Debug,
         2 => LevelFilter::Trace,
         _ => {
-            println!("[WARN] logger: invalid verbosity level, defaulting to none");
+            eprintln!("[WARN] logger: invalid verbosity level, defaulting to none");
             verbose = false;
             LevelFilter::Off
         }
@@ -46,7 +47,7 @@ pub fn setup_logger(matches: &ArgMatches) -> Result<(), fern::InitError> {
                 ))
             })
             .level(log_max_level)
-            .chain(std::io::stdout())
+            .chain(std::io::stderr())
             // .chain(fern::log_file("output.log")?)
             .apply()?;
     } else {
@@ -55,7 +56,7 @@ pub fn setup_logger(matches: &ArgMatches) -> Result<(), fern::InitError> {
                 out.finish(format_args!("[{}] {}", record.level(), message))
             })
             .level(log_max_level)
-            .chain(std::io::stdout())
+            .chain(std::io::stderr())
             // .chain(fern::log_file("output.log")?)
             .apply()?;
     }
@@ -3,14 +3,15 @@
 //! The `options` module defines elements necessary for the configuration of [`RuntimeConfig`] (contains
 //! the runtime options).
 
-use crate::config::cli::{build_cli, DEFAULT_OUTPUT_FILE};
+use crate::config::cli::{build_cli, DEFAULT_OUTPUT_FILE, STDOUT_OUTPUT_FILE};
 
 use super::{configs::Config, state::State};
 
-use std::{error::Error as StdErr, path::PathBuf};
+use std::{collections::btree_map::BTreeMap, error::Error as StdErr, path::PathBuf};
 
 use clap::ArgMatches;
 use clap_complete::Shell;
+use serde::{Deserialize, Serialize};
 
 /// `struct` containing runtime options gathered from the config file and command arguments.
 #[derive(Debug, Clone)]
@@ -27,14 +28,110 @@ pub struct RuntimeConfig {
     /// Exclusive operation specified by user.
     pub operation: Operation,
 
-    /// Path to output generated gitignore.
+    /// Path to output generated gitignore, [`STDOUT_OUTPUT_FILE`] for stdout.
     pub gitignore_output_file: String,
 
+    /// Choice of listing template bundles rather than templates.
+    pub list_bundles: bool,
+
+    /// Destination of the generated gitignore.
+    pub output_target: OutputTarget,
+
+    /// Ignore file format of the generated rules.
+    pub output_format: OutputFormat,
+
+    /// Choice of overwriting the output file rather than merging into its managed block.
+    pub force_overwrite: bool,
+
+    /// Project working tree to detect templates from.
+    pub detect_dir: Option<PathBuf>,
+
+    /// Choice of reporting the generation without writing the output file.
+    pub dry_run: bool,
+
+    /// Choice of regenerating the gitignore from the templates recorded in its lockfile.
+    pub locked: bool,
+
+    /// Granularity of the provenance annotations of the generated rules, if any.
+    pub annotation: Option<Annotation>,
+
+    /// Choice of ordering template sections alphabetically rather than in request order.
+    pub alpha_sort: bool,
+
+    /// Choice of deduplicating patterns across templates.
+    pub global_dedup: bool,
+
+    /// Choice of dropping the comment lines of templates.
+    pub strip_comments: bool,
+
+    /// Choice of dropping the blank lines of templates & between sections.
+    pub compact: bool,
+
+    /// Choice of trimming trailing whitespace & unifying line endings of templates.
+    pub normalize: bool,
+
+    /// Choice of reporting conflicting rules across templates.
+    pub check_conflicts: bool,
+
+    /// Choice of failing on conflicting rules across templates.
+    pub deny_conflicts: bool,
+
+    /// Template preprocessor variables set by the user.
+    pub template_variables: BTreeMap<String, String>,
+
     /// Shell to generate completions for.
     pub completion_shell: Shell,
 
     /// List of templates user desires to use in gitignore generation.
     pub templates: Vec<String>,
+
+    /// Name of the template to author.
+    pub template_name: String,
+
+    /// Local template source's path, relative to [`BaseRepoConfig::cache_dir`].
+    ///
+    /// [`BaseRepoConfig::cache_dir`]: super::configs::BaseRepoConfig::cache_dir
+    pub template_source: String,
+
+    /// Choice of backing the local template source with a git repository.
+    pub template_source_git: bool,
+
+    /// Path to the gitignore file captured as a template.
+    pub template_capture_file: PathBuf,
+}
+
+/// `enum` containing the destinations of a generated gitignore.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputTarget {
+    /// Option to write to [`RuntimeConfig::gitignore_output_file`].
+    File,
+    /// Option to write to the `info/exclude` file of the working directory's git repository.
+    Exclude,
+    /// Option to write to the global excludes file (`core.excludesFile`).
+    Global,
+}
+
+/// `enum` containing the ignore file formats of a generated gitignore.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Option to retain the gitignore rules.
+    #[default]
+    Gitignore,
+    /// Option to convert the rules to `.dockerignore` rules.
+    Dockerignore,
+    /// Option to convert the rules to `.hgignore` rules.
+    Hgignore,
+}
+
+/// `enum` containing the granularities of a generated gitignore's provenance annotations.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Annotation {
+    /// Option to annotate each section with its template files.
+    Section,
+    /// Option to annotate each rule with its template file & line.
+    Line,
 }
 
 /// `enum` containing exclusive operations that can be performed.
@@ -46,8 +143,24 @@ pub enum Operation {
     UpdateRepositories,
     /// Option to generate gitignore file.
     GenerateGitignore,
+    /// Option to check a generated gitignore file for drift.
+    CheckGitignore,
+    /// Option to preview the regeneration of a generated gitignore file.
+    DiffGitignore,
+    /// Option to regenerate a generated gitignore file from its recorded templates.
+    RegenerateGitignore,
+    /// Option to add templates to a generated gitignore file.
+    AddTemplates,
+    /// Option to remove templates from a generated gitignore file.
+    RemoveTemplates,
     /// Option to generate shell completion scripts.
     GenerateCompletions,
+    /// Option to create a template in a local template source.
+    NewTemplate,
+    /// Option to edit a template in a local template source.
+    EditTemplate,
+    /// Option to capture a gitignore file as a template in a local template source.
+    SaveTemplate,
     /// Option for unknown operations.
     Else,
 }
@@ -61,10 +174,31 @@ impl Default for RuntimeConfig {
             state: State::default(),
             operation: Operation::Else,
             gitignore_output_file: "".to_owned(),
+            list_bundles: false,
+            output_target: OutputTarget::File,
+            output_format: OutputFormat::Gitignore,
+            force_overwrite: false,
+            detect_dir: None,
+            dry_run: false,
+            locked: false,
+            alpha_sort: false,
+            annotation: None,
+            global_dedup: false,
+            strip_comments: false,
+            compact: false,
+            normalize: false,
+            check_conflicts: false,
+            deny_conflicts: false,
+            template_variables: BTreeMap::new(),
 
             completion_shell: Shell::Zsh,
 
             templates: vec!["".to_string()],
+
+            template_name: "".to_owned(),
+            template_source: "".to_owned(),
+            template_source_git: false,
+            template_capture_file: PathBuf::new(),
         }
     }
 }
@@ -75,7 +209,7 @@ impl RuntimeConfig {
     pub fn load(&mut self) -> Result<RuntimeConfig, Box<dyn StdErr>> {
         use super::logger::setup_logger;
 
-        self.matches = build_cli().get_matches();
+        self.matches = build_cli(vec![]).get_matches();
         setup_logger(&self.matches)?;
 
         debug!("cli: parsed command {:#?}", self.matches.clone());
@@ -100,9 +234,18 @@ impl RuntimeConfig {
     /// This function checks for the presence of [`clap::Subcommand`]s & [`clap::Arg`]s as provided
     /// in the [`clap::ArgMatches`] struct.
     fn configure_operation(&mut self) {
-        use crate::config::cli::{COMPLETIONS_SUBCMD, GENERATE_SUBCMD, LIST_SUBCMD, UPDATE_SUBCMD};
+        use crate::config::cli::{
+            ADD_SUBCMD, ANNOTATE_LINE, ANNOTATE_SECTION, CHECK_SUBCMD, COMPLETIONS_SUBCMD,
+            DIFF_SUBCMD, GENERATE_SUBCMD, LIST_SUBCMD, OUTPUT_FORMAT_DOCKERIGNORE,
+            OUTPUT_FORMAT_HGIGNORE, OUTPUT_TARGET_EXCLUDE, OUTPUT_TARGET_GLOBAL, REGENERATE_SUBCMD,
+            REMOVE_SUBCMD, SORT_ALPHA, TEMPLATE_EDIT_SUBCMD, TEMPLATE_NEW_SUBCMD,
+            TEMPLATE_SAVE_SUBCMD, TEMPLATE_SUBCMD, UPDATE_SUBCMD,
+        };
         match self.matches.subcommand() {
-            Some((LIST_SUBCMD, _)) => self.operation = Operation::ListAvailableTemplates,
+            Some((LIST_SUBCMD, sub_matches)) => {
+                self.operation = Operation::ListAvailableTemplates;
+                self.list_bundles = sub_matches.get_flag("bundles");
+            }
             Some((UPDATE_SUBCMD, _)) => self.operation = Operation::UpdateRepositories,
             Some((GENERATE_SUBCMD, sub_matches)) => {
                 self.operation = Operation::GenerateGitignore;
@@ -113,11 +256,87 @@ impl RuntimeConfig {
                     .to_str()
                     .unwrap_or(DEFAULT_OUTPUT_FILE)
                     .clone_into(&mut self.gitignore_output_file);
-                if let Some(templates_arg) = sub_matches.get_many::<String>("template") {
-                    self.templates = templates_arg
-                        .map(|tmpl| tmpl.to_owned())
-                        .collect::<Vec<_>>()
+                if sub_matches.get_flag("stdout") {
+                    STDOUT_OUTPUT_FILE.clone_into(&mut self.gitignore_output_file);
                 }
+                self.output_target =
+                    match sub_matches.get_one::<String>("target").map(String::as_str) {
+                        Some(OUTPUT_TARGET_EXCLUDE) => OutputTarget::Exclude,
+                        Some(OUTPUT_TARGET_GLOBAL) => OutputTarget::Global,
+                        _ => OutputTarget::File,
+                    };
+                self.output_format =
+                    match sub_matches.get_one::<String>("format").map(String::as_str) {
+                        Some(OUTPUT_FORMAT_DOCKERIGNORE) => OutputFormat::Dockerignore,
+                        Some(OUTPUT_FORMAT_HGIGNORE) => OutputFormat::Hgignore,
+                        _ => OutputFormat::Gitignore,
+                    };
+                self.force_overwrite = sub_matches.get_flag("force_overwrite");
+                self.dry_run = sub_matches.get_flag("dry_run");
+                self.locked = sub_matches.get_flag("locked");
+                self.alpha_sort = sub_matches
+                    .get_one::<String>("sort")
+                    .is_some_and(|sort| sort == SORT_ALPHA);
+                self.annotation = match sub_matches
+                    .get_one::<String>("annotate")
+                    .map(String::as_str)
+                {
+                    Some(ANNOTATE_SECTION) => Some(Annotation::Section),
+                    Some(ANNOTATE_LINE) => Some(Annotation::Line),
+                    _ => None,
+                };
+                self.global_dedup = sub_matches.get_flag("global_dedup");
+                self.strip_comments = sub_matches.get_flag("strip_comments");
+                self.compact = sub_matches.get_flag("compact");
+                self.normalize = sub_matches.get_flag("normalize");
+                self.deny_conflicts = sub_matches.get_flag("deny_conflicts");
+                self.check_conflicts =
+                    self.deny_conflicts || sub_matches.get_flag("check_conflicts");
+                self.detect_dir = sub_matches.get_one::<PathBuf>("detect").cloned();
+                self.template_variables = sub_matches
+                    .get_many::<(String, String)>("set")
+                    .map(|variables_arg| variables_arg.cloned().collect())
+                    .unwrap_or_default();
+                self.templates = sub_matches
+                    .get_many::<String>("template")
+                    .map(|templates_arg| templates_arg.map(|tmpl| tmpl.to_owned()).collect())
+                    .unwrap_or_default();
+            }
+            Some((subcmd @ (CHECK_SUBCMD | DIFF_SUBCMD | REGENERATE_SUBCMD), sub_matches)) => {
+                self.operation = match subcmd {
+                    DIFF_SUBCMD => Operation::DiffGitignore,
+                    REGENERATE_SUBCMD => Operation::RegenerateGitignore,
+                    _ => Operation::CheckGitignore,
+                };
+                if subcmd == REGENERATE_SUBCMD {
+                    self.dry_run = sub_matches.get_flag("dry_run");
+                }
+
+                sub_matches
+                    .get_one::<PathBuf>("file")
+                    .expect("cli: unable to use default file")
+                    .to_str()
+                    .unwrap_or(DEFAULT_OUTPUT_FILE)
+                    .clone_into(&mut self.gitignore_output_file);
+            }
+            Some((subcmd @ (ADD_SUBCMD | REMOVE_SUBCMD), sub_matches)) => {
+                self.operation = if subcmd == ADD_SUBCMD {
+                    Operation::AddTemplates
+                } else {
+                    Operation::RemoveTemplates
+                };
+
+                sub_matches
+                    .get_one::<PathBuf>("output")
+                    .expect("cli: unable to use default output")
+                    .to_str()
+                    .unwrap_or(DEFAULT_OUTPUT_FILE)
+                    .clone_into(&mut self.gitignore_output_file);
+                self.dry_run = sub_matches.get_flag("dry_run");
+                self.templates = sub_matches
+                    .get_many::<String>("template")
+                    .map(|templates_arg| templates_arg.map(|tmpl| tmpl.to_owned()).collect())
+                    .unwrap_or_default();
             }
             Some((COMPLETIONS_SUBCMD, sub_matches)) => {
                 self.operation = Operation::GenerateCompletions;
@@ -125,18 +344,54 @@ impl RuntimeConfig {
                     .get_one::<Shell>("shell")
                     .expect("cli: unable to use default shell")
             }
+            Some((TEMPLATE_SUBCMD, sub_matches)) => {
+                self.operation = match sub_matches.subcommand() {
+                    Some((TEMPLATE_NEW_SUBCMD, _)) => Operation::NewTemplate,
+                    Some((TEMPLATE_EDIT_SUBCMD, _)) => Operation::EditTemplate,
+                    Some((TEMPLATE_SAVE_SUBCMD, _)) => Operation::SaveTemplate,
+                    _ => Operation::Else,
+                };
+
+                if let Some((_, template_matches)) = sub_matches.subcommand() {
+                    template_matches
+                        .get_one::<String>("name")
+                        .expect("cli: unable to use template name")
+                        .clone_into(&mut self.template_name);
+                    template_matches
+                        .get_one::<String>("source")
+                        .expect("cli: unable to use default template source")
+                        .clone_into(&mut self.template_source);
+                    self.template_source_git = template_matches.get_flag("git");
+                    if let Ok(Some(capture_file)) = template_matches.try_get_one::<PathBuf>("from")
+                    {
+                        capture_file.clone_into(&mut self.template_capture_file);
+                    }
+                }
+            }
             _ => self.operation = Operation::Else,
         }
     }
 
     /// Generates completions for shells defined in [`clap_complete::Shell`].
+    ///
+    /// The configured template aliases & bundles are offered as template completion candidates.
     pub fn generate_completions(&mut self) -> Result<(), Box<dyn StdErr>> {
+        use crate::lookup::BUNDLE_PREFIX;
         use clap_complete::generate;
         use std::io;
 
+        let mut template_completion_names: Vec<String> =
+            self.config.aliases.keys().cloned().collect();
+        template_completion_names.extend(
+            self.config
+                .bundles
+                .keys()
+                .map(|bundle| format!("{}{}", BUNDLE_PREFIX, bundle)),
+        );
+
         generate(
             self.completion_shell,
-            &mut build_cli(),
+            &mut build_cli(template_completion_names),
             crate_name!(),
             &mut io::stdout(),
         );
@@ -0,0 +1,237 @@
+// SPDX-License-Identifier: MIT
+
+//! The `convert` module defines the translation of generated gitignore content to the ignore file
+//! formats of other tools, retaining the meaning of each rule where possible.
+//!
+//! Rules lacking an equivalent are reported as warnings.
+
+use crate::rules::classify_line;
+
+/// Const specifying the `.hgignore` line selecting the glob syntax for the following rules.
+const HG_SYNTAX_GLOB: &str = "syntax: glob";
+
+/// Const specifying the `.hgignore` line selecting the regexp syntax for the following rules.
+const HG_SYNTAX_REGEXP: &str = "syntax: regexp";
+
+/// `struct` containing the components of a gitignore pattern relevant to its translation.
+struct PatternParts<'a> {
+    /// Whether the pattern only matches relative to the gitignore's directory, as it contains a
+    /// leading or inner `/`.
+    anchored: bool,
+
+    /// Whether the pattern only matches directories, as it ends with a `/`.
+    dir_only: bool,
+
+    /// Pattern lacking its leading & trailing `/`.
+    body: &'a str,
+}
+
+/// Splits a (normalized, non-negated) gitignore pattern into its [`PatternParts`].
+fn split_pattern(pattern: &str) -> PatternParts<'_> {
+    let dir_only = pattern.ends_with('/') && !pattern.ends_with("\\/");
+    let body = pattern.trim_end_matches('/');
+    let anchored = body.contains('/');
+
+    PatternParts {
+        anchored,
+        dir_only,
+        body: body.strip_prefix('/').unwrap_or(body),
+    }
+}
+
+/// Converts gitignore content to `.dockerignore` content.
+///
+/// Docker anchors every pattern to the build context's root & lacks per-directory ignore files,
+/// hence unanchored patterns are prefixed by `**/` to match at any depth.
+/// Negated character classes (`[!…]`) become `[^…]`, as expected by Go's `filepath.Match`.
+/// Directory-only patterns can't be expressed, their trailing `/` is dropped with a warning.
+/// Comments & blank lines are retained as is.
+pub fn to_dockerignore(gitignore: &str) -> String {
+    let mut dockerignore = String::new();
+
+    for line in gitignore.lines() {
+        let rule_line = classify_line(line);
+        let pattern = match rule_line.pattern() {
+            Some(pattern) => pattern,
+            None => {
+                dockerignore.push_str(line);
+                dockerignore.push('\n');
+                continue;
+            }
+        };
+
+        let parts = split_pattern(pattern);
+        if parts.dir_only {
+            warn!(
+                "convert: dockerignore lacks directory-only patterns, `{}` also matches files",
+                line.trim()
+            );
+        }
+
+        let body = parts.body.replace("[!", "[^");
+        let negation = if rule_line.is_negation() { "!" } else { "" };
+        let anchor = if parts.anchored || body.starts_with("**/") {
+            ""
+        } else {
+            "**/"
+        };
+
+        dockerignore.push_str(&format!("{}{}{}\n", negation, anchor, body));
+    }
+
+    dockerignore
+}
+
+/// Converts gitignore content to `.hgignore` content.
+///
+/// Mercurial's glob patterns match at any depth, hence unanchored patterns are retained within
+/// `syntax: glob` sections.
+/// Anchored & directory-only patterns lack a glob equivalent, they're translated to regular
+/// expressions within `syntax: regexp` sections by [`glob_to_regexp`].
+/// Negations are unsupported by Mercurial, they're retained as comments with a warning.
+/// Comments & blank lines are retained as is.
+pub fn to_hgignore(gitignore: &str) -> String {
+    let mut hgignore = String::new();
+    let mut syntax: Option<&str> = None;
+
+    for line in gitignore.lines() {
+        let rule_line = classify_line(line);
+        let pattern = match rule_line.pattern() {
+            Some(pattern) => pattern,
+            None => {
+                hgignore.push_str(line);
+                hgignore.push('\n');
+                continue;
+            }
+        };
+
+        if rule_line.is_negation() {
+            warn!(
+                "convert: hgignore lacks negations, `{}` is commented out",
+                line.trim()
+            );
+            hgignore.push_str(&format!("# (unsupported negation) {}\n", line.trim()));
+            continue;
+        }
+
+        let parts = split_pattern(pattern);
+        let unrooted_body = parts.body.strip_prefix("**/");
+        let (rule_syntax, rule) = match (parts.anchored, parts.dir_only, unrooted_body) {
+            (false, false, _) => (HG_SYNTAX_GLOB, parts.body.to_owned()),
+            (true, false, Some(body)) => (HG_SYNTAX_GLOB, body.to_owned()),
+            (_, dir_only, unrooted_body) => {
+                let (prefix, body) = match unrooted_body {
+                    Some(body) => ("(?:^|/)", body),
+                    None if parts.anchored => ("^", parts.body),
+                    None => ("(?:^|/)", parts.body),
+                };
+                let suffix = if dir_only { "/" } else { "(?:/|$)" };
+
+                (
+                    HG_SYNTAX_REGEXP,
+                    format!("{}{}{}", prefix, glob_to_regexp(body), suffix),
+                )
+            }
+        };
+
+        if syntax != Some(rule_syntax) {
+            hgignore.push_str(rule_syntax);
+            hgignore.push('\n');
+            syntax = Some(rule_syntax);
+        }
+        hgignore.push_str(&rule);
+        hgignore.push('\n');
+    }
+
+    hgignore
+}
+
+/// Translates a gitignore glob to a regular expression, matching the same paths.
+///
+/// `*` & `?` don't match a `/`, while `**/` matches any (possibly empty) sequence of directories
+/// & any other `**` matches anything.
+fn glob_to_regexp(glob: &str) -> String {
+    let mut regexp = String::new();
+    let mut glob_chars = glob.chars().peekable();
+
+    while let Some(c) = glob_chars.next() {
+        match c {
+            '*' if glob_chars.peek() == Some(&'*') => {
+                glob_chars.next();
+                if glob_chars.peek() == Some(&'/') {
+                    glob_chars.next();
+                    regexp.push_str("(?:.*/)?");
+                } else {
+                    regexp.push_str(".*");
+                }
+            }
+            '*' => regexp.push_str("[^/]*"),
+            '?' => regexp.push_str("[^/]"),
+            '[' => {
+                regexp.push('[');
+                if glob_chars.peek() == Some(&'!') {
+                    glob_chars.next();
+                    regexp.push('^');
+                }
+                for class_char in glob_chars.by_ref() {
+                    if class_char == '\\' {
+                        regexp.push_str("\\\\");
+                        continue;
+                    }
+                    regexp.push(class_char);
+                    if class_char == ']' {
+                        break;
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(escaped_char) = glob_chars.next() {
+                    push_escaped(&mut regexp, escaped_char);
+                }
+            }
+            c => push_escaped(&mut regexp, c),
+        }
+    }
+
+    regexp
+}
+
+/// Appends a character to a regular expression, escaping regular expression metacharacters.
+fn push_escaped(regexp: &mut String, c: char) {
+    if ".^$*+?()[]{}|\\#".contains(c) {
+        regexp.push('\\');
+    }
+    regexp.push(c);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert gitignore rules retain their meaning as dockerignore rules.
+    fn to_dockerignore_test() {
+        let gitignore =
+            "# Rust\n\n/target\n*.o\nbuild/\ndocs/_build\n**/node_modules/\n!keep.o\n*.[!a]\n";
+
+        assert_eq!(
+            to_dockerignore(gitignore),
+            "# Rust\n\ntarget\n**/*.o\n**/build\ndocs/_build\n**/node_modules\n!**/keep.o\n**/*.[^a]\n"
+        );
+    }
+
+    #[test]
+    /// Assert gitignore rules are converted to glob & regexp hgignore rules.
+    fn to_hgignore_test() {
+        let gitignore = "# Rust\n/target\n*.o\nbuild/\n**/docs/_build\n!keep.o\n*.log\n";
+
+        assert_eq!(
+            to_hgignore(gitignore),
+            "# Rust\nsyntax: regexp\n^target(?:/|$)\nsyntax: glob\n*.o\nsyntax: regexp\n(?:^|/)build/\n\
+            syntax: glob\ndocs/_build\n# (unsupported negation) !keep.o\n*.log\n"
+        );
+        assert_eq!(glob_to_regexp("src/**/*.rs"), "src/(?:.*/)?[^/]*\\.rs");
+        assert_eq!(glob_to_regexp("a/**"), "a/.*");
+        assert_eq!(glob_to_regexp("[!a]?.c"), "[^a][^/]\\.c");
+    }
+}
@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: MIT
+
+//! The `detect` module defines functions necessary for detecting the technologies used within a
+//! project's working tree, mapping them to gitignore template names.
+
+use std::collections::btree_map::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// `Binary tree hash-map` alias mapping detected template names to the reasons for detection.
+pub type Detections = BTreeMap<String, Vec<String>>;
+
+/// `Binary tree hash-map` alias counting source files per (extension, template name) pair.
+type ExtensionHistogram = BTreeMap<(&'static str, &'static str), usize>;
+
+/// Const specifying the marker files (by exact name) indicating a template's technology.
+const MARKER_FILES: &[(&str, &str)] = &[
+    ("Cargo.toml", "Rust"),
+    ("package.json", "Node"),
+    ("pyproject.toml", "Python"),
+    ("requirements.txt", "Python"),
+    ("setup.py", "Python"),
+    ("Pipfile", "Python"),
+    ("go.mod", "Go"),
+    ("Gemfile", "Ruby"),
+    ("composer.json", "Composer"),
+    ("pom.xml", "Maven"),
+    ("build.gradle", "Gradle"),
+    ("build.gradle.kts", "Gradle"),
+    ("CMakeLists.txt", "CMake"),
+    ("mix.exs", "Elixir"),
+    ("pubspec.yaml", "Dart"),
+    ("stack.yaml", "Haskell"),
+    ("Package.swift", "Swift"),
+    ("main.tf", "Terraform"),
+];
+
+/// Const specifying the marker file extensions indicating a template's technology.
+const MARKER_EXTENSIONS: &[(&str, &str)] = &[
+    ("csproj", "VisualStudio"),
+    ("sln", "VisualStudio"),
+    ("vcxproj", "VisualStudio"),
+    ("cabal", "Haskell"),
+    ("tf", "Terraform"),
+];
+
+/// Const specifying the source file extensions counted in the extension histogram.
+const SOURCE_EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("js", "Node"),
+    ("ts", "Node"),
+    ("rb", "Ruby"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("hpp", "C++"),
+    ("cs", "VisualStudio"),
+    ("swift", "Swift"),
+    ("scala", "Scala"),
+    ("hs", "Haskell"),
+    ("ex", "Elixir"),
+    ("dart", "Dart"),
+    ("lua", "Lua"),
+    ("tex", "TeX"),
+];
+
+/// Const specifying directories skipped during detection, typically holding generated or vendored
+/// content.
+const SKIPPED_DIRECTORIES: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "venv",
+    "__pycache__",
+    "build",
+    "dist",
+];
+
+/// Const specifying the maximum directory depth scanned during detection.
+const DETECTION_DEPTH_LIMIT: usize = 4;
+
+/// Const specifying the minimum number of source files for an extension histogram detection.
+const EXTENSION_COUNT_MIN: usize = 3;
+
+/// Const specifying the minimum share (percentage) of source files for an extension histogram
+/// detection.
+const EXTENSION_SHARE_MIN: usize = 10;
+
+/// Detects the templates applicable to a project's working tree.
+///
+/// This function scans the working tree for marker files (see [`MARKER_FILES`] &
+/// [`MARKER_EXTENSIONS`]) & builds a histogram of source file extensions (see
+/// [`SOURCE_EXTENSIONS`]), yielding the detected template names alongside the reasons for their
+/// detection.
+pub fn detect_templates(root: &Path) -> io::Result<Detections> {
+    debug!("detect: scanning working tree {}", root.display());
+
+    let mut detections = Detections::new();
+    let mut extension_histogram = ExtensionHistogram::new();
+
+    scan_directory(root, root, 0, &mut detections, &mut extension_histogram)?;
+
+    let source_file_count: usize = extension_histogram.values().sum();
+    for ((extension, template), count) in extension_histogram {
+        let share = count * 100 / source_file_count;
+        if count < EXTENSION_COUNT_MIN || share < EXTENSION_SHARE_MIN {
+            continue;
+        }
+
+        detections
+            .entry(template.to_owned())
+            .or_default()
+            .push(format!(
+                "{} `*.{}` source files ({}% of source files)",
+                count, extension, share
+            ));
+    }
+
+    debug!("detect: detected templates {:#?}", detections);
+
+    Ok(detections)
+}
+
+/// Recurses on the content of a directory, updating the detections & extension histogram.
+fn scan_directory(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    detections: &mut Detections,
+    extension_histogram: &mut ExtensionHistogram,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+
+        let file_name = match entry.file_name().into_string() {
+            Ok(file_name) => file_name,
+            Err(_) => continue,
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        if entry_path.is_dir() {
+            if depth < DETECTION_DEPTH_LIMIT && !SKIPPED_DIRECTORIES.contains(&file_name.as_str()) {
+                scan_directory(
+                    root,
+                    &entry_path,
+                    depth + 1,
+                    detections,
+                    extension_histogram,
+                )?;
+            }
+
+            continue;
+        }
+
+        let relative_path = entry_path.strip_prefix(root).unwrap_or(&entry_path);
+
+        for (marker, template) in MARKER_FILES {
+            if file_name == *marker {
+                detections
+                    .entry(template.to_string())
+                    .or_default()
+                    .push(format!("`{}` marker file", relative_path.display()));
+            }
+        }
+
+        let extension = match entry_path.extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => extension,
+            None => continue,
+        };
+
+        for (marker_extension, template) in MARKER_EXTENSIONS {
+            if extension == *marker_extension {
+                detections
+                    .entry(template.to_string())
+                    .or_default()
+                    .push(format!("`{}` marker file", relative_path.display()));
+            }
+        }
+
+        if let Some(source_extension) = SOURCE_EXTENSIONS.iter().find(|(ext, _)| *ext == extension)
+        {
+            *extension_histogram.entry(*source_extension).or_default() += 1;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert the detection of marker files, marker extensions & histogram extensions, skipping
+    /// directories & sparse extensions.
+    fn detect_templates_test() {
+        let root = std::env::temp_dir().join(format!("ignore-detect-{}", std::process::id()));
+        let files = [
+            "Cargo.toml",
+            "app.csproj",
+            "node_modules/package.json",
+            ".venv/go.mod",
+        ]
+        .iter()
+        .map(|file| file.to_string())
+        .chain((0..30).map(|i| format!("src/{}.rs", i)))
+        .chain((0..5).map(|i| format!("scripts/{}.py", i)))
+        .chain((0..3).map(|i| format!("scripts/{}.lua", i)))
+        .chain((0..2).map(|i| format!("tools/{}.go", i)));
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let detections = detect_templates(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            detections.keys().collect::<Vec<_>>(),
+            vec!["Python", "Rust", "VisualStudio"]
+        );
+        assert_eq!(
+            detections["Rust"],
+            vec![
+                "`Cargo.toml` marker file",
+                "30 `*.rs` source files (75% of source files)"
+            ]
+        );
+        assert_eq!(
+            detections["Python"],
+            vec!["5 `*.py` source files (12% of source files)"]
+        );
+    }
+}
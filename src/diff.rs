@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT
+
+//! The `diff` module defines functions necessary for rendering the changes between two versions
+//! of a gitignore file as a unified diff.
+
+/// Const specifying the amount of unchanged lines surrounding the changes of a hunk.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Const specifying the ANSI escape sequence coloring removed lines.
+const COLOR_REMOVED: &str = "\x1b[31m";
+
+/// Const specifying the ANSI escape sequence coloring added lines.
+const COLOR_ADDED: &str = "\x1b[32m";
+
+/// Const specifying the ANSI escape sequence coloring hunk headers.
+const COLOR_HUNK: &str = "\x1b[36m";
+
+/// Const specifying the ANSI escape sequence coloring file headers.
+const COLOR_FILE: &str = "\x1b[1m";
+
+/// Const specifying the ANSI escape sequence resetting the color.
+const COLOR_RESET: &str = "\x1b[0m";
+
+/// `enum` containing a line's change between two versions of some content.
+#[derive(Debug, PartialEq)]
+enum DiffLine<'a> {
+    /// Line present in both versions.
+    Context(&'a str),
+
+    /// Line present in the old version only.
+    Removed(&'a str),
+
+    /// Line present in the new version only.
+    Added(&'a str),
+}
+
+/// Computes the line changes between two versions of some content, by their longest common
+/// subsequence.
+fn diff_lines<'a>(old_lines: &[&'a str], new_lines: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let (old_len, new_len) = (old_lines.len(), new_lines.len());
+
+    // Length of the longest common subsequence of `old_lines[i..]` & `new_lines[j..]`.
+    let mut lcs_lengths = vec![vec![0usize; new_len + 1]; old_len + 1];
+    for i in (0..old_len).rev() {
+        for j in (0..new_len).rev() {
+            lcs_lengths[i][j] = if old_lines[i] == new_lines[j] {
+                lcs_lengths[i + 1][j + 1] + 1
+            } else {
+                lcs_lengths[i + 1][j].max(lcs_lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::<DiffLine>::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old_len && j < new_len {
+        if old_lines[i] == new_lines[j] {
+            diff.push(DiffLine::Context(old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if lcs_lengths[i + 1][j] >= lcs_lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(old_lines[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new_lines[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old_lines[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(new_lines[j..].iter().map(|line| DiffLine::Added(line)));
+
+    diff
+}
+
+/// Renders the changes between two versions of some content as a unified diff.
+///
+/// Each hunk header names the closest heading (as determined by `is_heading` on the new version's
+/// lines) preceding the hunk's first change, grouping the hunks by section.
+/// An empty string is returned for identical versions.
+pub fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    is_heading: &dyn Fn(&[&str], usize) -> bool,
+) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let diff = diff_lines(&old_lines, &new_lines);
+
+    // Line offsets within the old & new versions preceding each diff line.
+    let mut offsets = Vec::<(usize, usize)>::with_capacity(diff.len() + 1);
+    let (mut old_offset, mut new_offset) = (0, 0);
+    for line in diff.iter() {
+        offsets.push((old_offset, new_offset));
+        match line {
+            DiffLine::Context(_) => {
+                old_offset += 1;
+                new_offset += 1;
+            }
+            DiffLine::Removed(_) => old_offset += 1,
+            DiffLine::Added(_) => new_offset += 1,
+        }
+    }
+    offsets.push((old_offset, new_offset));
+
+    let mut hunks = Vec::<(usize, usize)>::new();
+    for (index, _) in diff
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Context(_)))
+    {
+        let start = index.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (index + 1 + DIFF_CONTEXT_LINES).min(diff.len());
+
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut diff_string = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for (start, end) in hunks {
+        let (old_start, new_start) = offsets[start];
+        let (old_end, new_end) = offsets[end];
+
+        let (_, change_offset) = (start..end)
+            .find(|&index| !matches!(diff[index], DiffLine::Context(_)))
+            .map_or(offsets[start], |index| offsets[index]);
+        let heading = (0..change_offset)
+            .rev()
+            .find(|&index| is_heading(&new_lines, index))
+            .map(|index| format!(" {}", new_lines[index]))
+            .unwrap_or_default();
+
+        diff_string.push_str(&format!(
+            "@@ -{} +{} @@{}\n",
+            hunk_range(old_start, old_end - old_start),
+            hunk_range(new_start, new_end - new_start),
+            heading
+        ));
+
+        for line in diff[start..end].iter() {
+            let (prefix, line) = match line {
+                DiffLine::Context(line) => (' ', line),
+                DiffLine::Removed(line) => ('-', line),
+                DiffLine::Added(line) => ('+', line),
+            };
+            diff_string.push_str(&format!("{}{}\n", prefix, line));
+        }
+    }
+
+    diff_string
+}
+
+/// Formats a hunk's line range, the start being the preceding line for empty ranges.
+fn hunk_range(offset: usize, length: usize) -> String {
+    match length {
+        0 => format!("{},0", offset),
+        1 => format!("{}", offset + 1),
+        _ => format!("{},{}", offset + 1, length),
+    }
+}
+
+/// Colorizes a unified diff with ANSI escape sequences.
+pub fn colorize_diff(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            let color = if line.starts_with("---") || line.starts_with("+++") {
+                COLOR_FILE
+            } else if line.starts_with("@@") {
+                COLOR_HUNK
+            } else if line.starts_with('-') {
+                COLOR_REMOVED
+            } else if line.starts_with('+') {
+                COLOR_ADDED
+            } else {
+                return format!("{}\n", line);
+            };
+
+            format!("{}{}{}\n", color, line, COLOR_RESET)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert the hunks, their ranges & section headings of a unified diff.
+    fn unified_diff_test() {
+        let old = "# Rust\n# ----\ntarget/\n*.o\n# ----\n\n# Node\n# ----\nnode_modules/\n# ----\n";
+        let new = "# Rust\n# ----\ntarget/\n*.o\n# ----\n\n# Node\n# ----\nnode_modules/\ndist/\n# ----\n";
+        let is_heading = |lines: &[&str], index: usize| lines[index].starts_with("# N");
+
+        assert_eq!(
+            unified_diff(old, new, "a", "b", &is_heading),
+            "--- a\n+++ b\n@@ -7,4 +7,5 @@ # Node\n # Node\n # ----\n node_modules/\n+dist/\n # ----\n"
+        );
+        assert_eq!(
+            unified_diff("", "a\n", "a", "b", &is_heading),
+            "--- a\n+++ b\n@@ -0,0 +1 @@\n+a\n"
+        );
+        assert!(unified_diff(old, old, "a", "b", &is_heading).is_empty());
+    }
+}
@@ -5,19 +5,43 @@
 use std::error::Error as StdErr;
 use std::fmt::{Display, Formatter, Result};
 
+/// Const specifying the exit code for an [`ErrorKind::Drift`] error.
+const DRIFT_EXIT_CODE: i32 = 1;
+
+/// Const specifying the exit code for all other errors.
+const FAILURE_EXIT_CODE: i32 = 2;
+
 /// `enum` containing the possible kinds of errors for `ignore`.
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum ErrorKind {
+    /// Template bundle expansion encountered a cycle.
+    BundleCycle,
+
+    /// Generated gitignore file differs from its regenerated content or templates.
+    Drift,
+
     /// `dirs-next` failed to return the user's config directory.
     LocateConfigDir,
 
+    /// Locked template content could not be reproduced.
+    LockMismatch,
+
+    /// User requested template bundle not found.
+    MissingBundle,
+
     /// User requested templates not found.
     MissingTemplates,
 
     /// No output generated for specified action.
     NoOutput,
 
+    /// Template preprocessing failed on a malformed directive or include cycle.
+    Preprocessing,
+
+    /// Rules of the requested templates conflict with one another.
+    RuleConflicts,
+
     /// Error type for arbitrary (no fixed rule) errors.
     Other,
 }
@@ -28,7 +52,7 @@ pub struct Error {
     /// The kind of error as enumerated in [`ErrorKind`].
     kind: ErrorKind,
 
-    /// The message for an [`ErrorKind::Other`] error.
+    /// The message for an [`ErrorKind::Other`] error, details appended to other kinds' messages.
     other_message: String,
 
     // FIXME: Look into moving other_message into error; the `Option` will have to go.
@@ -52,6 +76,15 @@ impl Error {
         }
     }
 
+    /// Creates a new [`Error`] from a supplied [`ErrorKind`] & message detailing the error.
+    pub fn with_message(error_kind: ErrorKind, message: String) -> Self {
+        Self {
+            kind: error_kind,
+            other_message: message,
+            error: None,
+        }
+    }
+
     /// Returns the error's [`ErrorKind`].
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
@@ -62,10 +95,20 @@ impl Error {
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let message = match self.kind() {
+            ErrorKind::BundleCycle => "Template bundle references itself",
+            ErrorKind::Drift => "The gitignore file drifted from its template(s)",
+            ErrorKind::LockMismatch => {
+                "The locked gitignore template content could not be reproduced"
+            }
+            ErrorKind::MissingBundle => "The requested template bundle could not be found",
             ErrorKind::MissingTemplates => {
                 "None of the requested gitignore template(s) could be found"
             }
             ErrorKind::NoOutput => "No output was generated for the user specified operation",
+            ErrorKind::Preprocessing => "Failed to preprocess the gitignore template(s)",
+            ErrorKind::RuleConflicts => {
+                "The requested gitignore template(s) contain conflicting rules"
+            }
             ErrorKind::LocateConfigDir => "Failed to locate config directory",
             ErrorKind::Other => {
                 if self.other_message.is_empty() {
@@ -75,7 +118,12 @@ impl Display for Error {
                 }
             }
         };
-        write!(f, "{}", &message)
+
+        match self.kind() {
+            ErrorKind::Other => write!(f, "{}", &message),
+            _ if self.other_message.is_empty() => write!(f, "{}", &message),
+            _ => write!(f, "{}: {}", &message, &self.other_message),
+        }
     }
 }
 
@@ -121,3 +169,11 @@ impl From<&str> for Error {
         }
     }
 }
+
+/// Maps an error to the process exit code, distinguishing drift from other failures.
+pub fn exit_code(error: &(dyn StdErr + 'static)) -> i32 {
+    match error.downcast_ref::<Error>().map(Error::kind) {
+        Some(ErrorKind::Drift) => DRIFT_EXIT_CODE,
+        _ => FAILURE_EXIT_CODE,
+    }
+}
@@ -8,10 +8,17 @@ use crate::errors::Error;
 
 use git2::Repository;
 use std::error::Error as StdErr;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use rayon::prelude::*;
 
+/// Const specifying the path of a repository's exclude file, relative to its git directory.
+const EXCLUDE_FILE_PATH: &str = "info/exclude";
+
+/// Const specifying the git config key of the global excludes file.
+const EXCLUDES_FILE_CONFIG: &str = "core.excludesFile";
+
 /// Updates the cached gitignore template repositories (git only).
 ///
 /// This function fetches and merges the latest `HEAD` for an existing git repository, cloning one if
@@ -105,3 +112,141 @@ pub fn fetch_repository(
         absolute_repo_path!(app_conf, conf),
     )?)
 }
+
+/// Obtains the `HEAD` commit ID of a cached repository, `None` should it lack commits.
+pub fn head_commit_id(repo_dir: &Path) -> Option<String> {
+    let repo = Repository::open(repo_dir).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+
+    Some(commit.id().to_string())
+}
+
+/// Hashes content as a git blob, yielding the blob's ID.
+pub fn hash_blob(content: &[u8]) -> Result<String, Box<dyn StdErr>> {
+    use git2::{ObjectType, Oid};
+
+    Ok(Oid::hash_object(ObjectType::Blob, content)?.to_string())
+}
+
+/// Reads a file's content at a commit of a cached repository.
+pub fn read_blob(
+    repo_dir: &Path,
+    commit: &str,
+    relative_path: &Path,
+) -> Result<Vec<u8>, Box<dyn StdErr>> {
+    use git2::Oid;
+
+    let repo = Repository::open(repo_dir)?;
+    let tree = repo.find_commit(Oid::from_str(commit)?)?.tree()?;
+    let blob = tree
+        .get_path(relative_path)?
+        .to_object(&repo)?
+        .peel_to_blob()?;
+
+    Ok(blob.content().to_vec())
+}
+
+/// Obtains the path to the `info/exclude` file of the git repository containing a directory.
+pub fn exclude_file_path(dir: &Path) -> Result<PathBuf, Box<dyn StdErr>> {
+    let repo = Repository::discover(dir)?;
+    debug!("git: discovered repository {}", repo.path().display());
+
+    Ok(repo.path().join(EXCLUDE_FILE_PATH))
+}
+
+/// Obtains the path to the global excludes file, as configured by `core.excludesFile`.
+///
+/// Should `core.excludesFile` be unset, git's default (`$XDG_CONFIG_HOME/git/ignore`) is returned,
+/// the git config being left as is (see [`configure_global_excludes_file`]).
+pub fn global_excludes_file_path() -> Result<PathBuf, Box<dyn StdErr>> {
+    use git2::Config;
+    use std::env;
+
+    let config = Config::open_default()?;
+    if let Ok(excludes_file_path) = config.get_path(EXCLUDES_FILE_CONFIG) {
+        return Ok(excludes_file_path);
+    }
+
+    let home_dir =
+        dirs_next::home_dir().ok_or_else(|| Error::from("failed to locate home directory"))?;
+    let xdg_config_dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir.join(".config"));
+
+    Ok(xdg_config_dir.join("git/ignore"))
+}
+
+/// Sets `core.excludesFile` within the global git config to the global excludes file, should it
+/// be unset.
+pub fn configure_global_excludes_file(excludes_file_path: &Path) -> Result<(), Box<dyn StdErr>> {
+    use git2::Config;
+
+    let mut config = Config::open_default()?;
+    if config.get_path(EXCLUDES_FILE_CONFIG).is_ok() {
+        return Ok(());
+    }
+
+    let mut global_config = match config.open_global() {
+        Ok(global_config) => global_config,
+        Err(_) => {
+            let home_dir = dirs_next::home_dir()
+                .ok_or_else(|| Error::from("failed to locate home directory"))?;
+            Config::open(&home_dir.join(".gitconfig"))?
+        }
+    };
+    global_config.set_str(EXCLUDES_FILE_CONFIG, &excludes_file_path.to_string_lossy())?;
+    info!(
+        "git: set {} to {}",
+        EXCLUDES_FILE_CONFIG,
+        excludes_file_path.display()
+    );
+
+    Ok(())
+}
+
+/// Initializes a git repository for a local template source, opening an existing one.
+pub fn init_repository(repo_dir: &Path) -> Result<Repository, Box<dyn StdErr>> {
+    if let Ok(repo) = Repository::open(repo_dir) {
+        return Ok(repo);
+    }
+
+    info!("git: initializing template repo {}", repo_dir.display());
+
+    Ok(Repository::init(repo_dir)?)
+}
+
+/// Commits a file within a local template source's git repository.
+///
+/// The commit is signed by the user's git identity, falling back to an `ignore` identity.
+pub fn commit_file(
+    repo: &Repository,
+    relative_path: &Path,
+    message: &str,
+) -> Result<(), Box<dyn StdErr>> {
+    use git2::Signature;
+
+    let mut index = repo.index()?;
+    index.add_path(relative_path)?;
+    index.write()?;
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now(crate_name!(), "ignore@localhost"))?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<_> = parent.iter().collect();
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    debug!("git: committed {}", relative_path.display());
+
+    Ok(())
+}
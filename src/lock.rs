@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT
+
+//! The `lock` module defines the [`Lockfile`] recording the template files & options used in
+//! generating a gitignore file, allowing the (byte-identical) regeneration of the gitignore file.
+//!
+//! The lockfile is written alongside the generated gitignore file (i.e. `.gitignore.lock` for
+//! `.gitignore`).
+
+use crate::config::cli::{DEFAULT_CAPTURE_FILE, STDOUT_OUTPUT_FILE};
+use crate::config::runtime::{Annotation, OutputFormat};
+use crate::errors::Error;
+
+use std::collections::btree_map::BTreeMap;
+use std::error::Error as StdErr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Const specifying the suffix appended to the output file's path to locate its lockfile.
+const LOCK_FILE_SUFFIX: &str = ".lock";
+
+/// Const specifying the comment preceding a lockfile's content.
+const LOCK_FILE_HEADER: &str = "# This file is generated by `ignore generate`, do not edit.\n\n";
+
+/// `struct` containing the templates, options & template files used in generating a gitignore
+/// file.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Lockfile {
+    /// Templates used in generating the gitignore file.
+    pub templates: Vec<String>,
+
+    /// Template arguments (with bundles & aliases expanded) resolved to the templates, re-resolved
+    /// in regenerating the gitignore file.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<String>,
+
+    /// Options used in generating the gitignore file.
+    pub options: LockedOptions,
+
+    /// Template files read in generating the gitignore file, included templates' files too.
+    #[serde(rename = "file")]
+    pub files: Vec<LockedFile>,
+}
+
+/// `struct` containing the options affecting the content of a generated gitignore file.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LockedOptions {
+    /// Ignore file format of the generated rules.
+    pub format: OutputFormat,
+
+    /// Choice of ordering template sections alphabetically rather than in request order.
+    pub alpha_sort: bool,
+
+    /// Granularity of the provenance annotations of the generated rules, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotate: Option<Annotation>,
+
+    /// Choice of deduplicating patterns across templates.
+    pub global_dedup: bool,
+
+    /// Choice of dropping the comment lines of templates.
+    pub strip_comments: bool,
+
+    /// Choice of dropping the blank lines of templates & between sections.
+    pub compact: bool,
+
+    /// Choice of trimming trailing whitespace & unifying line endings of templates.
+    pub normalize: bool,
+
+    /// Template preprocessor variables set by the user.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+}
+
+/// `struct` containing the source & content hash of a template file.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct LockedFile {
+    /// Template (or included template) the file was read for.
+    pub template: String,
+
+    /// Cache path of the template's repository, as in [`RepoConfig::path`].
+    ///
+    /// [`RepoConfig::path`]: crate::config::configs::RepoConfig::path
+    pub repo: String,
+
+    /// URL of the template's repository, empty for local template sources.
+    pub url: String,
+
+    /// Path of the template file relative to the repository's root.
+    pub path: String,
+
+    /// Commit the file's content was read at, absent for content lacking a commit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// Git blob hash of the file's content.
+    pub hash: String,
+}
+
+/// Method implementations for [`Lockfile`].
+impl Lockfile {
+    /// Loads a lockfile.
+    pub fn load(lock_path: &Path) -> Result<Self, Box<dyn StdErr>> {
+        debug!("lock: loading {}", lock_path.display());
+
+        let lock_content = fs::read_to_string(lock_path).map_err(|err| {
+            Error::from(format!(
+                "failed to read lockfile {}: {}",
+                lock_path.display(),
+                err
+            ))
+        })?;
+
+        Ok(toml::from_str(&lock_content)?)
+    }
+
+    /// Saves the lockfile.
+    pub fn save(&self, lock_path: &Path) -> Result<(), Box<dyn StdErr>> {
+        fs::write(
+            lock_path,
+            format!("{}{}", LOCK_FILE_HEADER, toml::to_string(self)?),
+        )?;
+        debug!("lock: saved {}", lock_path.display());
+
+        Ok(())
+    }
+
+    /// Records a template file, once.
+    pub fn push_file(&mut self, locked_file: LockedFile) {
+        if !self.files.contains(&locked_file) {
+            self.files.push(locked_file);
+        }
+    }
+}
+
+/// Obtains the lockfile path of a gitignore output file.
+///
+/// The lockfile of a [`STDOUT_OUTPUT_FILE`] output is that of a [`DEFAULT_CAPTURE_FILE`] within
+/// the working directory.
+pub fn lock_file_path(output_file: &str) -> PathBuf {
+    let output_file = if output_file == STDOUT_OUTPUT_FILE {
+        DEFAULT_CAPTURE_FILE
+    } else {
+        output_file
+    };
+
+    PathBuf::from(format!("{}{}", output_file, LOCK_FILE_SUFFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert a lockfile survives its serialization.
+    fn lockfile_serialization_test() {
+        let lockfile = Lockfile {
+            templates: vec!["Rust".to_owned(), "github/gitignore:Node".to_owned()],
+            arguments: vec!["RUST".to_owned(), "github:node".to_owned()],
+            options: LockedOptions {
+                format: OutputFormat::Dockerignore,
+                alpha_sort: true,
+                annotate: Some(Annotation::Line),
+                global_dedup: true,
+                strip_comments: true,
+                compact: false,
+                normalize: true,
+                variables: BTreeMap::from([("ci".to_owned(), "true".to_owned())]),
+            },
+            files: vec![
+                LockedFile {
+                    template: "Rust".to_owned(),
+                    repo: "local/templates".to_owned(),
+                    url: "".to_owned(),
+                    path: "Rust.gitignore".to_owned(),
+                    commit: None,
+                    hash: "2f5a6e4b1f1e0d0b0d0c0b0a09080706050403".to_owned(),
+                },
+                LockedFile {
+                    template: "github/gitignore:Node".to_owned(),
+                    repo: "github/gitignore".to_owned(),
+                    url: "https://github.com/github/gitignore".to_owned(),
+                    path: "Node.gitignore".to_owned(),
+                    commit: Some("0e4c295b6048c30e2dc430e6ed40fa366eee5abe".to_owned()),
+                    hash: "9a1f0c2e3d4b5a69788796a5b4c3d2e1f0a9b8c7".to_owned(),
+                },
+            ],
+        };
+
+        let lock_content = toml::to_string(&lockfile).unwrap();
+        assert_eq!(toml::from_str::<Lockfile>(&lock_content).unwrap(), lockfile);
+
+        assert_eq!(
+            lock_file_path(".gitignore"),
+            PathBuf::from(".gitignore.lock")
+        );
+        assert_eq!(lock_file_path("-"), PathBuf::from(".gitignore.lock"));
+    }
+}
@@ -0,0 +1,411 @@
+// SPDX-License-Identifier: MIT
+
+//! The `lookup` module defines functions necessary for matching user-supplied template names to
+//! the available gitignore templates.
+
+use crate::config::configs::RepoConfig;
+use crate::errors::{Error, ErrorKind};
+
+use std::collections::btree_map::BTreeMap;
+
+/// Const specifying the prefix distinguishing template bundle names from template names.
+pub const BUNDLE_PREFIX: char = '@';
+
+/// Const specifying the delimiter between a repository qualifier & a template name.
+pub const REPO_QUALIFIER_DELIMITER: char = ':';
+
+/// Const specifying the file suffix ignored when matching template names.
+const TEMPLATE_FILE_SUFFIX: &str = ".gitignore";
+
+/// Const specifying the maximum number of suggestions offered for an unmatched template name.
+const SUGGESTION_LIMIT: usize = 3;
+
+/// Normalizes a template name for comparison.
+///
+/// This function lowercases the name, strips a trailing [`TEMPLATE_FILE_SUFFIX`] then drops
+/// spaces & hyphens, such that `visual-studio-code` & `VisualStudioCode.gitignore` are equal.
+pub fn normalize_template_name(name: &str) -> String {
+    let mut normalized_name = name.trim().to_lowercase();
+    if normalized_name.ends_with(TEMPLATE_FILE_SUFFIX) {
+        normalized_name.truncate(normalized_name.len() - TEMPLATE_FILE_SUFFIX.len());
+    }
+
+    normalized_name.retain(|c| c != ' ' && c != '-');
+    normalized_name
+}
+
+/// Splits a template name into its repository qualifier (if any) & the template name.
+///
+/// The name is split at the last [`REPO_QUALIFIER_DELIMITER`], such that a qualifier may be a URL
+/// (i.e. `https://github.com/toptal/gitignore:Rust`).
+pub fn split_repo_qualifier(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once(REPO_QUALIFIER_DELIMITER) {
+        Some((qualifier, template)) if !qualifier.is_empty() => (Some(qualifier), template),
+        _ => (None, name),
+    }
+}
+
+/// Checks whether a repository qualifier identifies a template repository.
+///
+/// The qualifier is compared to the repository's cache path (i.e. `github/gitignore`) & URL,
+/// wholly or by their trailing `/`-separated components (i.e. `toptal` or `toptal/gitignore`).
+pub fn match_repository(qualifier: &str, repo: &RepoConfig) -> bool {
+    let qualifier = qualifier.trim_matches('/').to_lowercase();
+    let repo_url = repo.url.trim_end_matches(".git").to_lowercase();
+    let repo_path = repo.path.to_lowercase();
+
+    [repo_path, repo_url].iter().any(|location| {
+        let location = location.trim_end_matches('/');
+        location == qualifier || location.ends_with(&format!("/{}", qualifier))
+    })
+}
+
+/// Selects the available template names matching a user-supplied template name.
+///
+/// All names equal under [`normalize_template_name`] are returned, an exact match first.
+pub fn match_template_names<'a, I>(name: &str, available_names: I) -> Vec<&'a String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let normalized_name = normalize_template_name(name);
+    let mut matched_names: Vec<&String> = available_names
+        .into_iter()
+        .filter(|available_name| normalize_template_name(available_name) == normalized_name)
+        .collect();
+
+    if let Some(index) = matched_names
+        .iter()
+        .position(|matched_name| *matched_name == name)
+    {
+        let exact_name = matched_names.remove(index);
+        matched_names.insert(0, exact_name);
+    }
+
+    matched_names
+}
+
+/// Checks whether a user-supplied template path matches a template's path.
+///
+/// The template path (relative to its repository, without the file type) matches should its
+/// trailing `/`-separated components equal those of the user-supplied path under
+/// [`normalize_template_name`], such that `python/jupyternotebooks` matches
+/// `community/Python/JupyterNotebooks`.
+/// A leading `/` anchors the user-supplied path to the repository's root, such that `/Node` only
+/// matches a root `Node` template.
+pub fn match_template_path(name: &str, template_path: &str) -> bool {
+    let name_components: Vec<String> = name
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .map(normalize_template_name)
+        .collect();
+    let path_components: Vec<String> = template_path
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .map(normalize_template_name)
+        .collect();
+
+    if name.starts_with('/') {
+        return path_components == name_components;
+    }
+
+    !name_components.is_empty() && path_components.ends_with(&name_components)
+}
+
+/// Checks whether a user-supplied template name identifies a template recorded by a generated
+/// gitignore.
+///
+/// A name qualified by a repository (see [`split_repo_qualifier`]) only matches recorded templates
+/// of the same qualifier, the template names being matched by [`match_template_path`].
+pub fn match_recorded_template(name: &str, recorded_template: &str) -> bool {
+    let (qualifier, template_name) = split_repo_qualifier(name);
+    let (recorded_qualifier, recorded_name) = split_repo_qualifier(recorded_template);
+
+    if qualifier.is_some() && qualifier != recorded_qualifier {
+        return false;
+    }
+
+    match_template_path(template_name, recorded_name)
+}
+
+/// Expands user-defined template aliases.
+///
+/// This function replaces template names matching an alias (as described in
+/// [`match_template_names`]) with the template name the alias expands to.
+pub fn expand_aliases(templates: &[String], aliases: &BTreeMap<String, String>) -> Vec<String> {
+    templates
+        .iter()
+        .map(
+            |template| match match_template_names(template, aliases.keys()).first() {
+                Some(alias) => {
+                    debug!("lookup: expanded alias {} to {}", alias, aliases[*alias]);
+                    aliases[*alias].to_owned()
+                }
+                None => template.to_owned(),
+            },
+        )
+        .collect()
+}
+
+/// Expands user-defined template bundles.
+///
+/// This function replaces [`BUNDLE_PREFIX`]ed names with the (recursively expanded) content of the
+/// matching bundle, keeping the first occurrence of each resulting name.
+/// An error is returned for unknown bundles & bundles referencing themselves.
+pub fn expand_bundles(
+    templates: &[String],
+    bundles: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<String>, Error> {
+    let mut expanded_templates = Vec::new();
+    let mut bundle_stack = Vec::new();
+
+    expand_bundle_names(
+        templates,
+        bundles,
+        &mut bundle_stack,
+        &mut expanded_templates,
+    )?;
+
+    Ok(expanded_templates)
+}
+
+/// Recurses on template names, appending the expanded names to the expanded templates.
+fn expand_bundle_names(
+    templates: &[String],
+    bundles: &BTreeMap<String, Vec<String>>,
+    bundle_stack: &mut Vec<String>,
+    expanded_templates: &mut Vec<String>,
+) -> Result<(), Error> {
+    for template in templates {
+        let bundle_name = match template.strip_prefix(BUNDLE_PREFIX) {
+            Some(bundle_name) => bundle_name,
+            None => {
+                if !expanded_templates.contains(template) {
+                    expanded_templates.push(template.to_owned());
+                }
+                continue;
+            }
+        };
+
+        let bundle = match match_template_names(bundle_name, bundles.keys()).first() {
+            Some(bundle) => bundle.to_string(),
+            None => {
+                return Err(Error::with_message(
+                    ErrorKind::MissingBundle,
+                    format!("`{}`", template),
+                ))
+            }
+        };
+
+        if bundle_stack.contains(&bundle) {
+            bundle_stack.push(bundle);
+            return Err(Error::with_message(
+                ErrorKind::BundleCycle,
+                bundle_stack
+                    .iter()
+                    .map(|name| format!("{}{}", BUNDLE_PREFIX, name))
+                    .collect::<Vec<_>>()
+                    .join(" -> "),
+            ));
+        }
+
+        debug!("lookup: expanding bundle {}", bundle);
+
+        let bundle_templates = &bundles[&bundle];
+        bundle_stack.push(bundle);
+        expand_bundle_names(bundle_templates, bundles, bundle_stack, expanded_templates)?;
+        bundle_stack.pop();
+    }
+
+    Ok(())
+}
+
+/// Suggests available template names similar to an unmatched template name.
+///
+/// This function ranks the available names by the edit distance between their normalized forms,
+/// returning at most [`SUGGESTION_LIMIT`] names within a length-relative distance threshold.
+/// The threshold is a single edit for short names (up to 3 characters), otherwise a third of the
+/// name's length (at least 2 edits).
+pub fn suggest_template_names<'a, I>(name: &str, available_names: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let normalized_name = normalize_template_name(name);
+    let threshold = match normalized_name.chars().count() {
+        0..=3 => 1,
+        name_length => (name_length / 3).max(2),
+    };
+
+    let mut ranked_names: Vec<(usize, &String)> = available_names
+        .into_iter()
+        .map(|available_name| {
+            let distance =
+                edit_distance(&normalized_name, &normalize_template_name(available_name));
+            (distance, available_name)
+        })
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect();
+    ranked_names.sort();
+
+    ranked_names
+        .into_iter()
+        .take(SUGGESTION_LIMIT)
+        .map(|(_, available_name)| available_name.to_owned())
+        .collect()
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(source: &str, target: &str) -> usize {
+    let target_chars: Vec<char> = target.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=target_chars.len()).collect();
+
+    for (i, source_char) in source.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+
+        for (j, target_char) in target_chars.iter().enumerate() {
+            let substitution_cost = usize::from(source_char != *target_char);
+            current_row.push(
+                (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1),
+            );
+        }
+        previous_row = current_row;
+    }
+
+    previous_row[target_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert template names match regardless of case, spaces, hyphens & file suffix.
+    fn match_template_names_test() {
+        let names: Vec<String> = ["Node", "Rust", "VisualStudioCode", "rust"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        assert_eq!(match_template_names("rust", &names), vec!["rust", "Rust"]);
+        assert_eq!(match_template_names("RUST", &names), vec!["Rust", "rust"]);
+        assert_eq!(
+            match_template_names("visual-studio-code", &names),
+            vec!["VisualStudioCode"]
+        );
+        assert_eq!(match_template_names("Node.gitignore", &names), vec!["Node"]);
+        assert!(match_template_names("Nod", &names).is_empty());
+    }
+
+    #[test]
+    /// Assert template paths match by their trailing components.
+    fn match_template_path_test() {
+        assert!(match_template_path("Global/macOS", "Global/macOS"));
+        assert!(match_template_path("global/mac-os", "Global/macOS"));
+        assert!(match_template_path(
+            "Python/JupyterNotebooks",
+            "community/Python/JupyterNotebooks"
+        ));
+        assert!(match_template_path("/Node", "Node"));
+        assert!(!match_template_path("/Node", "community/Python/Node"));
+        assert!(!match_template_path("Global/macOS", "macOS"));
+        assert!(!match_template_path("obal/macOS", "Global/macOS"));
+    }
+
+    #[test]
+    /// Assert recorded templates match by name, path & repository qualifier.
+    fn match_recorded_template_test() {
+        assert!(match_recorded_template("eclipse", "Eclipse"));
+        assert!(match_recorded_template("macOS", "Global/macOS"));
+        assert!(match_recorded_template("Node", "local/a:Node"));
+        assert!(match_recorded_template("local/a:node", "local/a:Node"));
+        assert!(!match_recorded_template("local/b:Node", "local/a:Node"));
+        assert!(!match_recorded_template("local/a:Node", "Node"));
+    }
+
+    #[test]
+    /// Assert aliases expand to their template names, leaving other names intact.
+    fn expand_aliases_test() {
+        let aliases: BTreeMap<String, String> = [("js", "Node"), ("mac", "macOS")]
+            .iter()
+            .map(|(alias, name)| (alias.to_string(), name.to_string()))
+            .collect();
+        let templates: Vec<String> = ["JS", "Rust", "mac"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        assert_eq!(
+            expand_aliases(&templates, &aliases),
+            vec!["Node", "Rust", "macOS"]
+        );
+    }
+
+    #[test]
+    /// Assert nested bundle expansion & cycle detection.
+    fn expand_bundles_test() {
+        let mut bundles: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        bundles.insert(
+            "os".to_owned(),
+            vec!["macOS".to_owned(), "Linux".to_owned()],
+        );
+        bundles.insert(
+            "web".to_owned(),
+            vec!["Node".to_owned(), "@os".to_owned(), "macOS".to_owned()],
+        );
+        let templates = vec!["Rust".to_owned(), "@web".to_owned()];
+
+        assert_eq!(
+            expand_bundles(&templates, &bundles).unwrap(),
+            vec!["Rust", "Node", "macOS", "Linux"]
+        );
+        assert!(expand_bundles(&["@missing".to_owned()], &bundles).is_err());
+
+        bundles.insert("os".to_owned(), vec!["@web".to_owned()]);
+        assert!(expand_bundles(&templates, &bundles).is_err());
+    }
+
+    #[test]
+    /// Assert repository qualifiers identify repositories by path & URL.
+    fn match_repository_test() {
+        let repo = RepoConfig {
+            auto_update: false,
+            skip: false,
+            path: "github/toptal".to_owned(),
+            url: "https://github.com/toptal/gitignore.git".to_owned(),
+            priority: 0,
+        };
+
+        assert_eq!(
+            split_repo_qualifier("toptal:rust"),
+            (Some("toptal"), "rust")
+        );
+        assert_eq!(
+            split_repo_qualifier("https://github.com/toptal/gitignore:Rust"),
+            (Some("https://github.com/toptal/gitignore"), "Rust")
+        );
+        assert_eq!(split_repo_qualifier("Rust"), (None, "Rust"));
+        assert!(match_repository("toptal", &repo));
+        assert!(match_repository("github/toptal", &repo));
+        assert!(match_repository("toptal/gitignore", &repo));
+        assert!(match_repository(
+            "https://github.com/toptal/gitignore",
+            &repo
+        ));
+        assert!(!match_repository("github/gitignore", &repo));
+    }
+
+    #[test]
+    /// Assert the ranking of suggestions for unmatched template names.
+    fn suggest_template_names_test() {
+        let names: Vec<String> = ["Node", "Nim", "Rust", "Go"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        assert_eq!(suggest_template_names("Nod", &names), vec!["Node"]);
+        assert_eq!(suggest_template_names("Gp", &names), vec!["Go"]);
+        assert_eq!(suggest_template_names("Rutss", &names), vec!["Rust"]);
+        assert!(suggest_template_names("Haskell", &names).is_empty());
+    }
+}
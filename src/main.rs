@@ -23,19 +23,31 @@ extern crate rayon;
 
 mod app;
 mod config;
+mod convert;
+mod detect;
+mod diff;
 mod errors;
 mod git;
+mod lock;
+mod lookup;
+mod managed;
+mod preprocess;
+mod provenance;
+mod rules;
+mod template;
 mod utils;
 
 use app::run;
 use config::runtime::RuntimeConfig;
+use errors::exit_code;
 
 /// This is the entry point for `ignore`'s binary.
 ///
 /// This function sets up the runtime environment [`RuntimeConfig`] then executes the specified operation.
+/// A failed operation exits with a non-zero code, as given by [`exit_code`].
 fn main() {
-    RuntimeConfig::default()
-        .load()
-        .map(|app_conf| run(app_conf).unwrap_or_else(|err| error!("app: failed with: {}", err)))
-        .unwrap_or_else(|err| error!("app: failed with: {}", err));
+    if let Err(err) = RuntimeConfig::default().load().and_then(run) {
+        error!("app: failed with: {}", err);
+        std::process::exit(exit_code(err.as_ref()));
+    }
 }
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT
+
+//! The `managed` module defines functions necessary for the maintenance of the `ignore` managed
+//! block within an existing gitignore file.
+//!
+//! The managed block wraps the generated gitignore content, allowing user-written rules above or
+//! below it to survive regeneration.
+
+use crate::errors::Error;
+
+/// Const specifying the line marking the start of the managed block.
+pub const MANAGED_BLOCK_START: &str = "# ---- BEGIN ignore managed block ----";
+
+/// Const specifying the line marking the end of the managed block.
+pub const MANAGED_BLOCK_END: &str = "# ---- END ignore managed block ----";
+
+/// Wraps generated gitignore content within the managed block markers.
+pub fn wrap_managed_block(generated: &str) -> String {
+    let mut block = format!("{}\n{}", MANAGED_BLOCK_START, generated);
+    if !block.ends_with('\n') {
+        block.push('\n');
+    }
+    block.push_str(&format!("{}\n", MANAGED_BLOCK_END));
+
+    block
+}
+
+/// Locates the managed block within some gitignore content.
+///
+/// This function returns the byte range spanning the start marker to the end of the end marker's
+/// line (inclusive of its line feed), `None` if no start marker exists.
+/// An error is returned for a start marker lacking an end marker, an end marker lacking a start
+/// marker & nested or repeated managed blocks, rather than merging into a corrupt block.
+pub fn find_managed_block(content: &str) -> Result<Option<(usize, usize)>, Error> {
+    let mut start = None;
+    let mut block = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed_line = line.trim_end();
+        let is_marker = trimmed_line == MANAGED_BLOCK_START || trimmed_line == MANAGED_BLOCK_END;
+
+        match (block, start) {
+            (Some(_), _) if is_marker => {
+                return Err(Error::from(
+                    "gitignore contains more than one managed block".to_owned(),
+                ))
+            }
+            (None, None) if trimmed_line == MANAGED_BLOCK_END => {
+                return Err(Error::from(format!(
+                    "managed block end marker lacks a matching `{}` line",
+                    MANAGED_BLOCK_START
+                )))
+            }
+            (None, None) if trimmed_line == MANAGED_BLOCK_START => start = Some(offset),
+            (None, Some(_)) if trimmed_line == MANAGED_BLOCK_START => {
+                return Err(Error::from(
+                    "managed block contains a nested managed block".to_owned(),
+                ))
+            }
+            (None, Some(start)) if trimmed_line == MANAGED_BLOCK_END => {
+                block = Some((start, offset + line.len()))
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+
+    match (block, start) {
+        (None, Some(_)) => Err(Error::from(format!(
+            "managed block start marker lacks a matching `{}` line",
+            MANAGED_BLOCK_END
+        ))),
+        _ => Ok(block),
+    }
+}
+
+/// Strips the managed block (markers included) from some gitignore content, retaining the
+/// user-written content surrounding it.
+pub fn strip_managed_block(content: &str) -> Result<String, Error> {
+    let (start, end) = match find_managed_block(content)? {
+        Some(block) => block,
+        None => return Ok(content.to_owned()),
+    };
+
+    let (preceding, following) = (&content[..start], &content[end..]);
+    if !following.trim().is_empty() {
+        return Ok(format!("{}{}", preceding, following));
+    }
+
+    let preceding = preceding.trim_end();
+    if preceding.is_empty() {
+        return Ok(String::new());
+    }
+
+    Ok(format!("{}\n", preceding))
+}
+
+/// Merges generated gitignore content into some existing gitignore content.
+///
+/// This function replaces the existing managed block with the generated content, preserving all
+/// content above & below it.
+/// Should the existing content lack a managed block, the generated content is appended to it.
+/// An error is returned for generated content containing managed block markers.
+pub fn merge_managed_block(existing: &str, generated: &str) -> Result<String, Error> {
+    if generated
+        .lines()
+        .any(|line| line.trim_end() == MANAGED_BLOCK_START || line.trim_end() == MANAGED_BLOCK_END)
+    {
+        return Err(Error::from(
+            "generated content contains managed block markers, strip them from its templates"
+                .to_owned(),
+        ));
+    }
+
+    let block = wrap_managed_block(generated);
+
+    if let Some((start, end)) = find_managed_block(existing)? {
+        return Ok(format!(
+            "{}{}{}",
+            &existing[..start],
+            block,
+            &existing[end..]
+        ));
+    }
+
+    if existing.trim().is_empty() {
+        return Ok(block);
+    }
+
+    let mut merged = existing.to_owned();
+    if !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    merged.push('\n');
+    merged.push_str(&block);
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert the preservation of user content around the managed block & idempotence.
+    fn merge_managed_block_test() {
+        let generated = "# Templates used: Rust\ntarget/\n";
+        let existing = format!(
+            "/local\n{}\nold/\n{}\n\n*.secret\n",
+            MANAGED_BLOCK_START, MANAGED_BLOCK_END
+        );
+
+        let merged = merge_managed_block(&existing, generated).unwrap();
+        assert_eq!(
+            merged,
+            format!("/local\n{}\n*.secret\n", wrap_managed_block(generated))
+        );
+        assert_eq!(merge_managed_block(&merged, generated).unwrap(), merged);
+    }
+
+    #[test]
+    /// Assert the managed block is appended to content lacking one.
+    fn merge_unmanaged_content_test() {
+        let generated = "target/\n";
+
+        let merged = merge_managed_block("/local", generated).unwrap();
+        assert_eq!(
+            merged,
+            format!("/local\n\n{}", wrap_managed_block(generated))
+        );
+        assert_eq!(merge_managed_block(&merged, generated).unwrap(), merged);
+
+        assert!(merge_managed_block(MANAGED_BLOCK_START, generated).is_err());
+    }
+
+    #[test]
+    /// Assert nested & repeated managed blocks are rejected, not merged into.
+    fn find_nested_managed_block_test() {
+        let block = wrap_managed_block("target/\n");
+
+        let nested = format!("{}{}{}\n", MANAGED_BLOCK_START, block, MANAGED_BLOCK_END);
+        assert!(find_managed_block(&nested).is_err());
+        assert!(find_managed_block(&format!("{}{}", block, block)).is_err());
+        assert!(find_managed_block(&format!("{}\n", MANAGED_BLOCK_END)).is_err());
+        assert!(merge_managed_block("", &block).is_err());
+    }
+
+    #[test]
+    /// Assert stripping the managed block retains the surrounding user content.
+    fn strip_managed_block_test() {
+        let merged = merge_managed_block("/local\n", "target/\n").unwrap();
+        assert_eq!(strip_managed_block(&merged).unwrap(), "/local\n");
+
+        let surrounded = format!("/local\n{}*.secret\n", wrap_managed_block("target/\n"));
+        assert_eq!(
+            strip_managed_block(&surrounded).unwrap(),
+            "/local\n*.secret\n"
+        );
+        assert!(strip_managed_block(&wrap_managed_block("target/\n"))
+            .unwrap()
+            .is_empty());
+    }
+}
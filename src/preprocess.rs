@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: MIT
+
+//! The `preprocess` module defines the template preprocessor, resolving directives & variable
+//! placeholders within gitignore templates.
+//!
+//! The supported directives are:
+//!
+//! - `#!include <TEMPLATE>`: inserts the (preprocessed) content of another template.
+//! - `#!if <EXPR>`, `#!else` & `#!endif`: conditionally retains lines, where `<EXPR>` is either
+//!   `<var> == "<value>"`, `<var> != "<value>"`, `<var>` (set & non-empty) or `!<var>`.
+//!
+//! Other lines starting with `#!` (i.e. a shebang within an upstream template) are retained as
+//! comments.
+//!
+//! Variable placeholders take the form `{{<var>}}`, unknown variables are left as is.
+
+use crate::errors::{Error, ErrorKind};
+
+use std::collections::btree_map::BTreeMap;
+use std::error::Error as StdErr;
+use std::path::Path;
+
+/// `Binary tree hash-map` alias mapping variable names to their values.
+pub type Variables = BTreeMap<String, String>;
+
+/// `Function` alias loading the raw content of an included template by name.
+pub type IncludeLoader<'a> = dyn Fn(&str) -> Result<String, Box<dyn StdErr>> + 'a;
+
+/// Const specifying the prefix of preprocessor directive lines.
+const DIRECTIVE_PREFIX: &str = "#!";
+
+/// Const specifying the keywords of preprocessor directives.
+const DIRECTIVE_KEYWORDS: &[&str] = &["include", "if", "else", "endif"];
+
+/// Const specifying the opening delimiter of a variable placeholder.
+const PLACEHOLDER_START: &str = "{{";
+
+/// Const specifying the closing delimiter of a variable placeholder.
+const PLACEHOLDER_END: &str = "}}";
+
+/// `struct` containing the preprocessor's variables & template loader.
+pub struct Preprocessor<'a> {
+    /// Variables substituted into placeholders & evaluated by conditionals.
+    variables: &'a Variables,
+
+    /// Loads the raw content of an included template by name.
+    include_loader: &'a IncludeLoader<'a>,
+}
+
+/// `struct` containing the state of an `#!if` block.
+struct Conditional {
+    /// Whether the enclosing blocks retain their lines.
+    parent_active: bool,
+
+    /// Whether the `#!if` expression held.
+    condition: bool,
+
+    /// Whether the `#!else` branch was entered.
+    in_else: bool,
+}
+
+/// Method implementations for [`Conditional`].
+impl Conditional {
+    /// Checks whether the block currently retains its lines.
+    fn is_active(&self) -> bool {
+        self.parent_active && (self.condition != self.in_else)
+    }
+}
+
+/// Method implementations for [`Preprocessor`].
+impl<'a> Preprocessor<'a> {
+    /// Creates a new [`Preprocessor`] from the variables & a template loader.
+    pub fn new(variables: &'a Variables, include_loader: &'a IncludeLoader<'a>) -> Self {
+        Self {
+            variables,
+            include_loader,
+        }
+    }
+
+    /// Preprocesses a template's content.
+    ///
+    /// An error is returned for malformed directives, unbalanced conditionals & include cycles.
+    pub fn process(&self, template: &str, content: &str) -> Result<String, Box<dyn StdErr>> {
+        let mut include_stack = vec![template.to_owned()];
+
+        self.process_content(content, &mut include_stack)
+    }
+
+    /// Preprocesses template content, tracking the chain of included templates.
+    fn process_content(
+        &self,
+        content: &str,
+        include_stack: &mut Vec<String>,
+    ) -> Result<String, Box<dyn StdErr>> {
+        let mut processed_content = String::new();
+        let mut conditionals = Vec::<Conditional>::new();
+        let template = include_stack.last().cloned().unwrap_or_default();
+
+        for (index, line) in content.lines().enumerate() {
+            let active = conditionals.last().is_none_or(Conditional::is_active);
+            let directive_error = |message: &str| {
+                Box::new(Error::with_message(
+                    ErrorKind::Preprocessing,
+                    format!("{} line {}: {}", template, index + 1, message),
+                ))
+            };
+
+            let directive = line
+                .trim()
+                .strip_prefix(DIRECTIVE_PREFIX)
+                .map(|directive| {
+                    let directive = directive.trim();
+                    directive
+                        .split_once(char::is_whitespace)
+                        .map(|(keyword, argument)| (keyword, argument.trim()))
+                        .unwrap_or((directive, ""))
+                })
+                .filter(|(keyword, _)| DIRECTIVE_KEYWORDS.contains(keyword));
+
+            let (keyword, argument) = match directive {
+                Some(directive) => directive,
+                None => {
+                    if active {
+                        processed_content.push_str(&self.substitute(line));
+                        processed_content.push('\n');
+                    }
+                    continue;
+                }
+            };
+
+            match keyword {
+                "include" if argument.is_empty() => {
+                    return Err(directive_error("`#!include` lacks a template name"))
+                }
+                "include" if !active => {}
+                "include" => {
+                    if include_stack.iter().any(|template| template == argument) {
+                        return Err(directive_error(&format!(
+                            "include cycle {} -> {}",
+                            include_stack.join(" -> "),
+                            argument
+                        )));
+                    }
+
+                    debug!("preprocess: including {}", argument);
+
+                    let included_content = (self.include_loader)(argument)?;
+                    include_stack.push(argument.to_owned());
+                    processed_content
+                        .push_str(&self.process_content(&included_content, include_stack)?);
+                    include_stack.pop();
+                }
+                "if" => {
+                    let condition = self
+                        .evaluate(argument)
+                        .ok_or_else(|| directive_error("malformed `#!if` expression"))?;
+                    conditionals.push(Conditional {
+                        parent_active: active,
+                        condition,
+                        in_else: false,
+                    });
+                }
+                "else" => match conditionals.last_mut() {
+                    Some(conditional) if !conditional.in_else => conditional.in_else = true,
+                    _ => return Err(directive_error("`#!else` lacks a matching `#!if`")),
+                },
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(directive_error("`#!endif` lacks a matching `#!if`"));
+                    }
+                }
+                _ => unreachable!("preprocess: unknown directive keyword {}", keyword),
+            }
+        }
+
+        if !conditionals.is_empty() {
+            return Err(Box::new(Error::with_message(
+                ErrorKind::Preprocessing,
+                format!("{}: `#!if` lacks a matching `#!endif`", template),
+            )));
+        }
+
+        Ok(processed_content)
+    }
+
+    /// Evaluates a conditional expression, `None` for malformed expressions.
+    fn evaluate(&self, expression: &str) -> Option<bool> {
+        for (operator, equality) in [("==", true), ("!=", false)] {
+            if let Some((variable, value)) = expression.split_once(operator) {
+                let value = value.trim().trim_matches('"');
+                let variable_value = self
+                    .variables
+                    .get(variable.trim())
+                    .map(String::as_str)
+                    .unwrap_or_default();
+
+                return Some((variable_value == value) == equality);
+            }
+        }
+
+        let (variable, negated) = match expression.strip_prefix('!') {
+            Some(variable) => (variable.trim(), true),
+            None => (expression, false),
+        };
+        if variable.is_empty() || variable.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let is_set = self
+            .variables
+            .get(variable)
+            .is_some_and(|value| !value.is_empty());
+
+        Some(is_set != negated)
+    }
+
+    /// Substitutes the known variable placeholders within a line.
+    fn substitute(&self, line: &str) -> String {
+        let mut substituted_line = String::new();
+        let mut remaining_line = line;
+
+        while let Some(start) = remaining_line.find(PLACEHOLDER_START) {
+            let placeholder = &remaining_line[start + PLACEHOLDER_START.len()..];
+            let end = match placeholder.find(PLACEHOLDER_END) {
+                Some(end) => end,
+                None => break,
+            };
+
+            substituted_line.push_str(&remaining_line[..start]);
+            match self.variables.get(placeholder[..end].trim()) {
+                Some(value) => substituted_line.push_str(value),
+                None => {
+                    warn!(
+                        "preprocess: unknown variable in placeholder `{}{}{}`",
+                        PLACEHOLDER_START,
+                        &placeholder[..end],
+                        PLACEHOLDER_END
+                    );
+                    substituted_line.push_str(
+                        &remaining_line
+                            [start..start + PLACEHOLDER_START.len() + end + PLACEHOLDER_END.len()],
+                    );
+                }
+            }
+            remaining_line = &placeholder[end + PLACEHOLDER_END.len()..];
+        }
+        substituted_line.push_str(remaining_line);
+
+        substituted_line
+    }
+}
+
+/// Collects the preprocessor variables for a project.
+///
+/// The built-in `os`, `arch` & `family` variables describe the host, while `project_name` is read
+/// from the project's manifest (`Cargo.toml` or `pyproject.toml`), defaulting to the project
+/// directory's name.
+pub fn project_variables(project_dir: &Path) -> Variables {
+    use std::env::consts;
+
+    let mut variables = Variables::new();
+    variables.insert("os".to_owned(), consts::OS.to_owned());
+    variables.insert("arch".to_owned(), consts::ARCH.to_owned());
+    variables.insert("family".to_owned(), consts::FAMILY.to_owned());
+
+    let project_name = manifest_project_name(project_dir).or_else(|| {
+        project_dir
+            .canonicalize()
+            .ok()?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    });
+    if let Some(project_name) = project_name {
+        variables.insert("project_name".to_owned(), project_name);
+    }
+
+    debug!("preprocess: project variables {:#?}", variables);
+
+    variables
+}
+
+/// Reads the project name from a project's TOML manifest.
+fn manifest_project_name(project_dir: &Path) -> Option<String> {
+    use std::fs;
+
+    let manifests = [
+        ("Cargo.toml", ["package", "name"]),
+        ("pyproject.toml", ["project", "name"]),
+        ("pyproject.toml", ["tool", "poetry"]),
+    ];
+
+    for (manifest, [table, key]) in manifests {
+        let manifest_content = match fs::read_to_string(project_dir.join(manifest)) {
+            Ok(manifest_content) => manifest_content,
+            Err(_) => continue,
+        };
+        let manifest_value: toml::Value = match toml::from_str(&manifest_content) {
+            Ok(manifest_value) => manifest_value,
+            Err(err) => {
+                warn!("preprocess: failed to parse {}: {}", manifest, err);
+                continue;
+            }
+        };
+
+        let mut value = manifest_value.get(table).and_then(|value| value.get(key));
+        if key == "poetry" {
+            value = value.and_then(|value| value.get("name"));
+        }
+        if let Some(name) = value.and_then(|value| value.as_str()) {
+            return Some(name.to_owned());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads templates from a fixed set for testing.
+    fn test_loader(name: &str) -> Result<String, Box<dyn StdErr>> {
+        match name {
+            "Node" => Ok("node_modules/\n".to_owned()),
+            "Loop" => Ok("#!include Base\n".to_owned()),
+            _ => Err(Box::new(Error::from(ErrorKind::MissingTemplates))),
+        }
+    }
+
+    #[test]
+    /// Assert the resolution of includes, conditionals & placeholders.
+    fn process_test() {
+        let mut variables = Variables::new();
+        variables.insert("os".to_owned(), "linux".to_owned());
+        variables.insert("project_name".to_owned(), "app".to_owned());
+        let preprocessor = Preprocessor::new(&variables, &test_loader);
+
+        let content = "#!include Node\n\
+            #!if os == \"linux\"\n\
+            /{{project_name}}\n\
+            #!if !ci\n\
+            .local/\n\
+            #!endif\n\
+            #!else\n\
+            *.exe\n\
+            #!endif\n\
+            {{ unknown }}\n\
+            #!/usr/bin/env sh\n";
+        assert_eq!(
+            preprocessor.process("Base", content).unwrap(),
+            "node_modules/\n/app\n.local/\n{{ unknown }}\n#!/usr/bin/env sh\n"
+        );
+
+        assert!(preprocessor.process("Base", "#!include Loop\n").is_err());
+        assert!(preprocessor.process("Base", "#!if os\n").is_err());
+        assert!(preprocessor.process("Base", "#!endif\n").is_err());
+    }
+}
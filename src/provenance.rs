@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT
+
+//! The `provenance` module defines the annotation of generated gitignore sections with the origin
+//! of their rules, as comment lines of the form `# <- <repo>:<path>[:<line>]`.
+//!
+//! Annotations precede the annotated content, since gitignore lacks trailing comments.
+
+use crate::rules::classify_line;
+
+/// Const specifying the prefix of provenance annotation lines.
+const ANNOTATION_PREFIX: &str = "# <- ";
+
+/// `struct` containing the content of a template file read in generating a section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+    /// Origin of the file, its repository & relative path (i.e. `github/gitignore:Node.gitignore`).
+    pub origin: String,
+
+    /// Raw (unprocessed) content of the file.
+    pub content: String,
+}
+
+/// Annotates a section with the origins of its source files, once each.
+pub fn annotate_section(section: &str, sources: &[SourceFile]) -> String {
+    let mut annotated_section = String::new();
+    let mut origins = Vec::<&str>::new();
+
+    for source in sources.iter() {
+        if !origins.contains(&source.origin.as_str()) {
+            origins.push(&source.origin);
+            annotated_section.push_str(&format!("{}{}\n", ANNOTATION_PREFIX, source.origin));
+        }
+    }
+    annotated_section.push_str(section);
+
+    annotated_section
+}
+
+/// Locates the source file line each rule of a section stems from, as `<origin>:<line>`.
+///
+/// The sources are expected in the order their content was consolidated, the primary file first.
+/// A rule's source line is the first one holding the same normalized rule (see [`classify_line`])
+/// not yet claimed by a previous rule, falling back to the first such line should all be claimed.
+/// Rules lacking a source line (i.e. holding substituted placeholders) are located by the first
+/// source's origin alone, while other lines aren't located.
+pub fn locate_rules(section: &str, sources: &[SourceFile]) -> Vec<Option<String>> {
+    let mut claimed_lines = Vec::<(usize, usize)>::new();
+
+    section
+        .lines()
+        .map(|line| {
+            let rule = classify_line(line);
+            rule.pattern()?;
+
+            let source_lines: Vec<(usize, usize)> = sources
+                .iter()
+                .enumerate()
+                .flat_map(|(source_index, source)| {
+                    source
+                        .content
+                        .lines()
+                        .enumerate()
+                        .filter(|(_, source_line)| classify_line(source_line) == rule)
+                        .map(move |(line_index, _)| (source_index, line_index))
+                })
+                .collect();
+            let source_line = source_lines
+                .iter()
+                .find(|source_line| !claimed_lines.contains(source_line))
+                .or_else(|| source_lines.first())
+                .copied();
+
+            match source_line {
+                Some((source_index, line_index)) => {
+                    claimed_lines.push((source_index, line_index));
+                    Some(format!(
+                        "{}:{}",
+                        sources[source_index].origin,
+                        line_index + 1
+                    ))
+                }
+                None => sources.first().map(|source| source.origin.to_owned()),
+            }
+        })
+        .collect()
+}
+
+/// Annotates each rule of a section with the origin of the source file line it stems from, as
+/// located by [`locate_rules`].
+pub fn annotate_lines(section: &str, sources: &[SourceFile]) -> String {
+    let mut annotated_section = String::new();
+
+    for (line, location) in section.lines().zip(locate_rules(section, sources)) {
+        match location {
+            Some(location) => {
+                annotated_section.push_str(&format!("{}{}\n", ANNOTATION_PREFIX, location))
+            }
+            None if classify_line(line).pattern().is_some() => {
+                warn!("provenance: no source for `{}`", line.trim())
+            }
+            None => {}
+        }
+
+        annotated_section.push_str(line);
+        annotated_section.push('\n');
+    }
+
+    annotated_section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert sections & rules are annotated with their origins.
+    fn annotate_test() {
+        let sources = vec![
+            SourceFile {
+                origin: "local/a:Node.gitignore".to_owned(),
+                content: "# Logs\n*.log\n/{{project_name}}\n".to_owned(),
+            },
+            SourceFile {
+                origin: "local/b:Node.gitignore".to_owned(),
+                content: "node_modules/\n**/*.log\n".to_owned(),
+            },
+        ];
+        let section = "# Logs\n*.log\n/app\nnode_modules/\n*.log\n";
+
+        assert_eq!(
+            annotate_section(section, &sources),
+            format!(
+                "# <- local/a:Node.gitignore\n# <- local/b:Node.gitignore\n{}",
+                section
+            )
+        );
+        assert_eq!(
+            annotate_lines(section, &sources),
+            "# Logs\n# <- local/a:Node.gitignore:2\n*.log\n# <- local/a:Node.gitignore\n/app\n\
+            # <- local/b:Node.gitignore:1\nnode_modules/\n# <- local/b:Node.gitignore:2\n*.log\n"
+        );
+    }
+}
@@ -0,0 +1,480 @@
+// SPDX-License-Identifier: MIT
+
+//! The `rules` module defines the classification & normalization of gitignore lines, and the
+//! operations acting on the rules of consolidated gitignore templates.
+
+use crate::provenance::{locate_rules, SourceFile};
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// `enum` containing the kinds of lines within a gitignore file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RuleLine {
+    /// Empty or whitespace-only line.
+    Blank,
+
+    /// Comment line, starting with an unescaped `#`.
+    Comment,
+
+    /// Negated pattern (re-including files), starting with an unescaped `!`; holds the normalized
+    /// pattern without the `!`.
+    Negation(String),
+
+    /// Pattern (ignoring files); holds the normalized pattern.
+    Pattern(String),
+}
+
+/// Method implementations for [`RuleLine`].
+impl RuleLine {
+    /// Returns the normalized pattern of a [`RuleLine::Negation`] or [`RuleLine::Pattern`].
+    pub fn pattern(&self) -> Option<&str> {
+        match self {
+            RuleLine::Negation(pattern) | RuleLine::Pattern(pattern) => Some(pattern),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the line is a [`RuleLine::Negation`].
+    pub fn is_negation(&self) -> bool {
+        matches!(self, RuleLine::Negation(_))
+    }
+}
+
+/// Classifies a gitignore line.
+///
+/// This function classifies the line following the `gitignore(5)` rules: `#` starts a comment,
+/// `!` negates a pattern & both are taken literally when escaped by a `\`.
+pub fn classify_line(line: &str) -> RuleLine {
+    let trimmed_line = trim_pattern(line);
+
+    if trimmed_line.is_empty() {
+        return RuleLine::Blank;
+    }
+    if trimmed_line.starts_with('#') {
+        return RuleLine::Comment;
+    }
+
+    match trimmed_line.strip_prefix('!') {
+        Some(pattern) if !pattern.is_empty() => RuleLine::Negation(normalize_pattern(pattern)),
+        Some(_) => RuleLine::Blank,
+        None => RuleLine::Pattern(normalize_pattern(trimmed_line)),
+    }
+}
+
+/// Trims a gitignore line, retaining trailing whitespace escaped by a `\`.
+pub fn trim_pattern(line: &str) -> &str {
+    let trimmed_line = line.trim_start().trim_end_matches(['\r', '\n']);
+    let end = trimmed_line.trim_end().len();
+
+    match trimmed_line[end..].chars().next() {
+        Some(whitespace) if trimmed_line[..end].ends_with('\\') => {
+            &trimmed_line[..end + whitespace.len_utf8()]
+        }
+        _ => &trimmed_line[..end],
+    }
+}
+
+/// Normalizes a (non-negated) gitignore pattern for comparison.
+///
+/// This function drops a redundant leading `**/` from patterns lacking an inner `/`, since such
+/// patterns match at any directory level regardless.
+pub fn normalize_pattern(pattern: &str) -> String {
+    if let Some(stripped_pattern) = pattern.strip_prefix("**/") {
+        if !stripped_pattern.trim_end_matches('/').contains('/') && !stripped_pattern.is_empty() {
+            return stripped_pattern.to_owned();
+        }
+    }
+
+    pattern.to_owned()
+}
+
+/// Checks whether two normalized patterns may match a common path.
+///
+/// This function over-approximates: the patterns may overlap should any of their `/`-separated
+/// components match one another, wildcard components (`*`, `?`, `[…]`) matched as globs & two
+/// wildcard components assumed to overlap should their literal prefixes & suffixes agree (see
+/// [`may_overlap_wildcards`]).
+pub fn may_overlap(pattern: &str, other_pattern: &str) -> bool {
+    let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let other_components: Vec<&str> = other_pattern.split('/').filter(|c| !c.is_empty()).collect();
+
+    components.iter().any(|component| {
+        other_components.iter().any(|other_component| {
+            match (is_wildcard(component), is_wildcard(other_component)) {
+                (true, true) => may_overlap_wildcards(component, other_component),
+                (true, false) => wildcard_match(component, other_component),
+                (false, true) => wildcard_match(other_component, component),
+                (false, false) => component == other_component,
+            }
+        })
+    })
+}
+
+/// Checks whether two wildcard components may match a common name.
+///
+/// The components may overlap should the literal prefix (preceding the first wildcard) of either
+/// start the other's & the literal suffix (following the last wildcard) of either end the other's,
+/// such that `*.txt` & `*.pyc` don't overlap while `*.log` & `debug*` do.
+fn may_overlap_wildcards(component: &str, other_component: &str) -> bool {
+    let (prefix, suffix) = literal_affixes(component);
+    let (other_prefix, other_suffix) = literal_affixes(other_component);
+
+    (prefix.starts_with(other_prefix) || other_prefix.starts_with(prefix))
+        && (suffix.ends_with(other_suffix) || other_suffix.ends_with(suffix))
+}
+
+/// Splits a wildcard component's literal prefix & suffix, surrounding its wildcards.
+fn literal_affixes(component: &str) -> (&str, &str) {
+    let prefix_end = component.find(['*', '?', '[']).unwrap_or(component.len());
+    let suffix_start = component
+        .rfind(['*', '?', ']'])
+        .map_or(component.len(), |index| index + 1);
+
+    (&component[..prefix_end], &component[suffix_start..])
+}
+
+/// Checks whether a pattern component contains wildcards.
+fn is_wildcard(component: &str) -> bool {
+    component.contains(['*', '?', '['])
+}
+
+/// Matches a text against a glob component, treating a `[…]` class as a single character
+/// wildcard.
+fn wildcard_match(glob: &str, text: &str) -> bool {
+    let mut glob_tokens = Vec::new();
+    let mut glob_chars = glob.chars();
+    while let Some(c) = glob_chars.next() {
+        match c {
+            '[' => {
+                for class_char in glob_chars.by_ref() {
+                    if class_char == ']' {
+                        break;
+                    }
+                }
+                glob_tokens.push(Some('?'));
+            }
+            '*' => glob_tokens.push(None),
+            c => glob_tokens.push(Some(c)),
+        }
+    }
+
+    let text_chars: Vec<char> = text.chars().collect();
+    // `matches[j]` holds whether the glob tokens so far match the first `j` text characters.
+    let mut matches = vec![false; text_chars.len() + 1];
+    matches[0] = true;
+
+    for token in glob_tokens {
+        let mut next_matches = vec![false; text_chars.len() + 1];
+        for j in 0..=text_chars.len() {
+            next_matches[j] = match token {
+                None => matches[j] || (j > 0 && next_matches[j - 1]),
+                Some('?') => j > 0 && matches[j - 1],
+                Some(c) => j > 0 && matches[j - 1] && text_chars[j - 1] == c,
+            };
+        }
+        matches = next_matches;
+    }
+
+    matches[text_chars.len()]
+}
+
+/// `struct` containing the location of a rule within the consolidated template sections.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSource {
+    /// Template file & line the rule stems from (as located by [`locate_rules`]), otherwise the
+    /// template & line within its section's content.
+    pub location: String,
+
+    /// The rule's line, trimmed.
+    pub line: String,
+}
+
+/// `struct` containing a pair of conflicting rules from different templates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleConflict {
+    /// The rule appearing first.
+    pub earlier: RuleSource,
+
+    /// The rule appearing last, overriding the earlier rule.
+    pub later: RuleSource,
+}
+
+/// [`std::fmt::Display`] trait implementation for [`RuleConflict`].
+impl Display for RuleConflict {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let action = if self.later.line.starts_with('!') {
+            "re-includes files ignored by"
+        } else {
+            "re-ignores files re-included by"
+        };
+
+        write!(
+            f,
+            "`{}` ({}) {} `{}` ({})",
+            self.later.line, self.later.location, action, self.earlier.line, self.earlier.location
+        )
+    }
+}
+
+/// Finds conflicting rules across consolidated template sections.
+///
+/// This function acts on `(template, content)` sections, pairing each rule with the rules of the
+/// opposite polarity (negation vs pattern) from earlier templates that it [`may_overlap`].
+/// The rules are located within the template files read for each section, as [`SourceFile`]s.
+pub fn find_conflicts(
+    sections: &[(String, String)],
+    section_sources: &[Vec<SourceFile>],
+) -> Vec<RuleConflict> {
+    let mut conflicts = Vec::new();
+    let mut earlier_rules = Vec::<(RuleLine, RuleSource)>::new();
+
+    for (section_index, (template, content)) in sections.iter().enumerate() {
+        let mut section_rules = Vec::new();
+        let locations = locate_rules(
+            content,
+            section_sources
+                .get(section_index)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        );
+
+        for ((index, line), location) in content.lines().enumerate().zip(locations) {
+            let rule = classify_line(line);
+            let pattern = match rule.pattern() {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+            let source = RuleSource {
+                location: location.unwrap_or_else(|| format!("{}:{}", template, index + 1)),
+                line: trim_pattern(line).to_owned(),
+            };
+
+            for (earlier_rule, earlier_source) in earlier_rules.iter() {
+                if earlier_rule.is_negation() != rule.is_negation()
+                    && may_overlap(pattern, earlier_rule.pattern().unwrap_or_default())
+                {
+                    conflicts.push(RuleConflict {
+                        earlier: earlier_source.clone(),
+                        later: source.clone(),
+                    });
+                }
+            }
+
+            section_rules.push((rule, source));
+        }
+
+        earlier_rules.extend(section_rules);
+    }
+
+    conflicts
+}
+
+/// Deduplicates patterns across consolidated template sections.
+///
+/// This function acts on `(template, content)` sections, keeping the first occurrence of each
+/// rule & replacing later occurrences with a `# <rule> (also in <template>)` note.
+/// A later occurrence is kept should a rule of the opposite polarity (negation vs pattern) that
+/// [`may_overlap`] it appear after the previous occurrence, as removing it would alter the rule
+/// ordering semantics.
+pub fn dedup_sections(sections: &mut [(String, String)]) {
+    use std::collections::HashMap;
+
+    // Maps a rule to the index & template of its last kept occurrence.
+    let mut kept_rules = HashMap::<RuleLine, (usize, String)>::new();
+    let mut rules = Vec::<RuleLine>::new();
+
+    for (template, content) in sections.iter_mut() {
+        let mut deduped_content = String::new();
+
+        for line in content.lines() {
+            let rule = classify_line(line);
+            let pattern = match rule.pattern() {
+                Some(pattern) => pattern,
+                None => {
+                    deduped_content.push_str(&format!("{}\n", line));
+                    continue;
+                }
+            };
+
+            let duplicate_template =
+                kept_rules
+                    .get(&rule)
+                    .and_then(|(kept_index, kept_template)| {
+                        let reordered = rules[*kept_index..].iter().any(|other_rule| {
+                            other_rule.is_negation() != rule.is_negation()
+                                && may_overlap(pattern, other_rule.pattern().unwrap_or_default())
+                        });
+
+                        (!reordered).then_some(kept_template)
+                    });
+
+            match duplicate_template {
+                Some(kept_template) => {
+                    debug!(
+                        "rules: `{}` in {} duplicates {}",
+                        line.trim(),
+                        template,
+                        kept_template
+                    );
+                    deduped_content.push_str(&format!(
+                        "# {} (also in {})\n",
+                        line.trim(),
+                        kept_template
+                    ));
+                }
+                None => {
+                    deduped_content.push_str(&format!("{}\n", line));
+                    kept_rules.insert(rule.clone(), (rules.len(), template.to_owned()));
+                }
+            }
+
+            rules.push(rule);
+        }
+
+        *content = deduped_content;
+    }
+}
+
+/// Tidies the content of a consolidated template section.
+///
+/// This function drops the comment lines (starting with a `#`) should `strip_comments` be set &
+/// the blank lines should `compact` be set.
+/// Should `normalize` be set, trailing whitespace is trimmed (retaining whitespace escaped by a
+/// `\`), while line endings are unified to `\n` regardless.
+pub fn tidy_section(content: &str, strip_comments: bool, compact: bool, normalize: bool) -> String {
+    let mut tidied_content = String::new();
+
+    for line in content.lines() {
+        if (strip_comments && line.starts_with('#')) || (compact && line.trim().is_empty()) {
+            continue;
+        }
+
+        let line = if normalize {
+            let trimmed_line = line.trim_end();
+            match line[trimmed_line.len()..].chars().next() {
+                Some(whitespace) if trimmed_line.ends_with('\\') => {
+                    &line[..trimmed_line.len() + whitespace.len_utf8()]
+                }
+                _ => trimmed_line,
+            }
+        } else {
+            line
+        };
+
+        tidied_content.push_str(line);
+        tidied_content.push('\n');
+    }
+
+    tidied_content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Assert the classification & normalization of gitignore lines.
+    fn classify_line_test() {
+        assert_eq!(classify_line("   "), RuleLine::Blank);
+        assert_eq!(classify_line("# comment"), RuleLine::Comment);
+        assert_eq!(
+            classify_line("\\#file"),
+            RuleLine::Pattern("\\#file".to_owned())
+        );
+        assert_eq!(
+            classify_line("!keep.log  "),
+            RuleLine::Negation("keep.log".to_owned())
+        );
+        assert_eq!(
+            classify_line("**/node_modules/"),
+            RuleLine::Pattern("node_modules/".to_owned())
+        );
+        assert_eq!(
+            classify_line("**/a/b"),
+            RuleLine::Pattern("**/a/b".to_owned())
+        );
+        assert_eq!(classify_line("a\\ "), RuleLine::Pattern("a\\ ".to_owned()));
+        assert_eq!(
+            classify_line("foo\\\u{a0}\u{a0}"),
+            RuleLine::Pattern("foo\\\u{a0}".to_owned())
+        );
+    }
+
+    #[test]
+    /// Assert cross-section deduplication respects negations.
+    fn dedup_sections_test() {
+        let mut sections = vec![
+            (
+                "Node".to_owned(),
+                "*.log\nnode_modules/\n# Logs\n".to_owned(),
+            ),
+            ("Yarn".to_owned(), "*.log\n!keep.log\n".to_owned()),
+            ("Other".to_owned(), "*.log\n**/node_modules/\n".to_owned()),
+        ];
+
+        dedup_sections(&mut sections);
+        assert_eq!(sections[0].1, "*.log\nnode_modules/\n# Logs\n");
+        assert_eq!(sections[1].1, "# *.log (also in Node)\n!keep.log\n");
+        assert_eq!(sections[2].1, "*.log\n# **/node_modules/ (also in Node)\n");
+    }
+
+    #[test]
+    /// Assert conflicts are only reported across templates.
+    fn find_conflicts_test() {
+        let sections = vec![
+            ("Node".to_owned(), "*.log\n!debug.log\n".to_owned()),
+            (
+                "Custom".to_owned(),
+                "# Logs\n!keep.log\nbuild/\n".to_owned(),
+            ),
+        ];
+
+        let section_sources = vec![
+            vec![SourceFile {
+                origin: "local/a:Node.gitignore".to_owned(),
+                content: "# Node\n*.log\n!debug.log\n".to_owned(),
+            }],
+            vec![],
+        ];
+
+        let conflicts = find_conflicts(&sections, &section_sources);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].to_string(),
+            "`!keep.log` (Custom:2) re-includes files ignored by `*.log` (local/a:Node.gitignore:2)"
+        );
+    }
+
+    #[test]
+    /// Assert the overlap approximation of patterns.
+    fn may_overlap_test() {
+        assert!(may_overlap("*.log", "keep.log"));
+        assert!(may_overlap("build/", "build/keep"));
+        assert!(may_overlap("*.log", "debug*"));
+        assert!(may_overlap("[Bb]in/", "bin"));
+        assert!(!may_overlap("node_modules/", "keep.log"));
+        assert!(!may_overlap("*.pyc", "keep.log"));
+        assert!(!may_overlap("*.txt", "*.pyc"));
+        assert!(may_overlap("*.py[cod]", "*.pyc"));
+        assert!(!may_overlap("debug-*.log", "trace*"));
+    }
+
+    #[test]
+    /// Assert the comment, blank lines & trailing whitespace are dropped as requested.
+    fn tidy_section_test() {
+        let content = "# Logs\r\n*.log  \r\n\n\\#file\nspace\\  \n";
+
+        assert_eq!(
+            tidy_section(content, false, false, false),
+            "# Logs\n*.log  \n\n\\#file\nspace\\  \n"
+        );
+        assert_eq!(
+            tidy_section(content, true, true, true),
+            "*.log\n\\#file\nspace\\ \n"
+        );
+        assert_eq!(
+            tidy_section(content, false, true, false),
+            "# Logs\n*.log  \n\\#file\nspace\\  \n"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT
+
+//! The `template` module defines user-executable tasks for authoring templates within a local
+//! template source.
+//!
+//! A local template source is a template repository lacking a URL, scanned like any cached
+//! template repository.
+
+use crate::config::{configs::RepoConfig, runtime::RuntimeConfig};
+use crate::errors::Error;
+use crate::git::{commit_file, init_repository};
+use crate::managed::strip_managed_block;
+use crate::utils::create_file;
+
+use std::error::Error as StdErr;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Const specifying the file type of authored templates.
+const TEMPLATE_FILE_TYPE: &str = "gitignore";
+
+/// Const specifying the editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Creates a template within the local template source & opens it in the user's editor.
+pub fn new_template(app_conf: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    let (source_dir, relative_path) = prepare_template_source(app_conf)?;
+    let template_path = source_dir.join(&relative_path);
+
+    if template_path.exists() {
+        return Err(Box::new(Error::from(format!(
+            "template {} exists, edit it instead",
+            template_path.display()
+        ))));
+    }
+
+    create_file(&template_path)?;
+    fs::write(&template_path, format!("# {}\n", app_conf.template_name))?;
+    open_editor(&template_path)?;
+
+    commit_template(app_conf, &source_dir, &relative_path, "Add")
+}
+
+/// Opens an existing template within the local template source in the user's editor.
+pub fn edit_template(app_conf: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    let (source_dir, relative_path) = prepare_template_source(app_conf)?;
+    let template_path = source_dir.join(&relative_path);
+
+    if !template_path.is_file() {
+        return Err(Box::new(Error::from(format!(
+            "template {} doesn't exist, create it instead",
+            template_path.display()
+        ))));
+    }
+
+    open_editor(&template_path)?;
+
+    commit_template(app_conf, &source_dir, &relative_path, "Update")
+}
+
+/// Captures an existing gitignore file as a template within the local template source.
+///
+/// Only the user-written content is captured, the managed block (see [`strip_managed_block`])
+/// being regenerated from its own templates.
+pub fn save_template(app_conf: &mut RuntimeConfig) -> Result<(), Box<dyn StdErr>> {
+    let template_content =
+        strip_managed_block(&fs::read_to_string(&app_conf.template_capture_file)?)?;
+    if template_content.trim().is_empty() {
+        return Err(Box::new(Error::from(format!(
+            "{} lacks content outside its managed block",
+            app_conf.template_capture_file.display()
+        ))));
+    }
+
+    let (source_dir, relative_path) = prepare_template_source(app_conf)?;
+    let template_path = source_dir.join(&relative_path);
+
+    if !template_path.exists() {
+        create_file(&template_path)?;
+    }
+    fs::write(&template_path, template_content)?;
+    info!(
+        "template: saved {} as {}",
+        app_conf.template_capture_file.display(),
+        template_path.display()
+    );
+
+    commit_template(app_conf, &source_dir, &relative_path, "Save")
+}
+
+/// Prepares the local template source for authoring.
+///
+/// This function creates the local template source's directory, registering it (with an empty
+/// URL) in the config file & initializing its git repository if desired.
+/// The source's directory & the template's path relative to it are returned.
+fn prepare_template_source(
+    app_conf: &mut RuntimeConfig,
+) -> Result<(PathBuf, PathBuf), Box<dyn StdErr>> {
+    use std::fs::DirBuilder;
+
+    let relative_path = PathBuf::from(format!("{}.{}", app_conf.template_name, TEMPLATE_FILE_TYPE));
+    let valid_name = relative_path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !valid_name {
+        return Err(Box::new(Error::from(format!(
+            "invalid template name {}",
+            app_conf.template_name
+        ))));
+    }
+
+    let source_dir =
+        Path::new(&app_conf.config.repository.cache_dir).join(&app_conf.template_source);
+
+    match app_conf
+        .config
+        .repository
+        .config
+        .iter()
+        .find(|conf| conf.path == app_conf.template_source)
+    {
+        Some(conf) if !conf.url.is_empty() => {
+            return Err(Box::new(Error::from(format!(
+                "template source {} is a remote repository",
+                conf.path
+            ))));
+        }
+        Some(_) => {}
+        None => {
+            app_conf.config.append_repository(RepoConfig {
+                auto_update: false,
+                skip: false,
+                path: app_conf.template_source.to_owned(),
+                url: "".to_owned(),
+                priority: 0,
+            })?;
+            info!(
+                "template: registered local template source {}",
+                app_conf.template_source
+            );
+        }
+    }
+
+    DirBuilder::new().recursive(true).create(&source_dir)?;
+    if app_conf.template_source_git {
+        init_repository(&source_dir)?;
+    }
+
+    Ok((source_dir, relative_path))
+}
+
+/// Opens a file in the user's editor (`$VISUAL`, `$EDITOR` or [`DEFAULT_EDITOR`]).
+fn open_editor(file_path: &Path) -> Result<(), Box<dyn StdErr>> {
+    use std::env;
+    use std::process::Command;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_owned());
+    let mut editor_args = editor.split_whitespace();
+
+    debug!("template: opening {} in {}", file_path.display(), editor);
+
+    let status = Command::new(editor_args.next().unwrap_or(DEFAULT_EDITOR))
+        .args(editor_args)
+        .arg(file_path)
+        .status()?;
+    if !status.success() {
+        return Err(Box::new(Error::from(format!(
+            "editor exited with {}",
+            status
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Commits a template to the local template source's git repository, if any.
+fn commit_template(
+    app_conf: &RuntimeConfig,
+    source_dir: &Path,
+    relative_path: &Path,
+    action: &str,
+) -> Result<(), Box<dyn StdErr>> {
+    if !app_conf.template_source_git && !source_dir.join(".git").is_dir() {
+        return Ok(());
+    }
+
+    let repo = init_repository(source_dir)?;
+    commit_file(
+        &repo,
+        relative_path,
+        &format!("{} {} template", action, app_conf.template_name),
+    )?;
+    info!(
+        "template: committed {} to {}",
+        relative_path.display(),
+        source_dir.display()
+    );
+
+    Ok(())
+}